@@ -1,50 +1,538 @@
-//! This build script generates the KANA_TO_ROMANJI phf::Map
-//! from the kana_to_romanji.csv file at the project root.
-use std::fs::File;
+//! This build script generates the TRANSLATIONS and KANA tables and the
+//! COMPOUND_TRANSLATIONS phf::Map from the kana_to_romanji.csv file at the project root,
+//! the ChartVersion enum and CHART_LAYOUTS table from the charts.csv file at the project root,
+//! the WORDS list from the words.csv file at the project root,
+//! the STROKES map from the KanjiVG formatted SVG files within the strokes directory,
+//! the KANA_TO_AUDIO map from the audio clips within the audio directory,
+//! and the CONFUSABLE_GROUPS table from the confusables.csv file at the project root.
+//!
+//! It also verifies that every kana within the charts has a translation,
+//!   and that every cell of each chart is laid out exactly once.
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
 type BuildResult = Result<(), Box<dyn std::error::Error>>;
+/// The kana of each cell of a chart keyed by (row, column), where empty cells are empty strings.
+type ChartCells = BTreeMap<(usize, usize), String>;
+/// The file extensions of the audio clips which are bundled.
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "ogg", "wav"];
+/// The chōonpu, which prolongs the vowel of the kana that precedes it.
+const CHOONPU: &str = "ー";
+/// The romanization systems of the spelling columns of kana_to_romanji.csv, in order.
+const SYSTEMS: [System; 4] = [System::Hepburn, System::KunreiShiki, System::NihonShiki, System::Ime];
+/// The files and directories which are generated into rust code.
+const INPUTS: &[&str] = &[
+    "build.rs",
+    "kana_to_romanji.csv",
+    "charts.csv",
+    "words.csv",
+    "confusables.csv",
+    "strokes",
+    "audio",
+];
+
+
+/// The romanization systems of kana_to_romanji.csv, which mirror the RomanizationSystem enum.
+#[derive(Copy, Clone, PartialEq)]
+enum System {
+    Hepburn,
+    KunreiShiki,
+    NihonShiki,
+    Ime,
+}
 
 
 fn main() {
+    // Declaring any input stops cargo from rerunning this script upon every change to the
+    //   package, so every input is declared, including the clips added to the audio directory.
+    for input in INPUTS {
+        println!("cargo:rerun-if-changed={}", input);
+    }
     generate_kana_to_romanji(
         Path::new("kana_to_romanji.csv"),
         &Path::new("src").join("kana_to_romanji_codegen.rs")
-    ).expect("failed to generate KANA_TO_ROMANJI");
+    ).expect("failed to generate TRANSLATIONS");
+    generate_kana(
+        Path::new("kana_to_romanji.csv"),
+        &Path::new("src").join("kana_codegen.rs")
+    ).expect("failed to generate KANA");
+    generate_charts(
+        Path::new("charts.csv"),
+        Path::new("kana_to_romanji.csv"),
+        &Path::new("src").join("charts_codegen.rs")
+    ).expect("failed to generate CHART_LAYOUTS");
+    generate_words(
+        Path::new("words.csv"),
+        &Path::new("src").join("words_codegen.rs")
+    ).expect("failed to generate WORDS");
+    generate_strokes(
+        Path::new("strokes"),
+        Path::new("kana_to_romanji.csv"),
+        &Path::new("src").join("strokes_codegen.rs")
+    ).expect("failed to generate STROKES");
+    generate_audio(
+        Path::new("audio"),
+        Path::new("kana_to_romanji.csv"),
+        &Path::new("src").join("audio_codegen.rs")
+    ).expect("failed to generate KANA_TO_AUDIO");
+    generate_confusables(
+        Path::new("confusables.csv"),
+        Path::new("kana_to_romanji.csv"),
+        &Path::new("src").join("confusables_codegen.rs")
+    ).expect("failed to generate CONFUSABLE_GROUPS");
 }
 
+/// Each kana is also generated joined with a sokuon and/or chōonpu, i.e. "っき", "キー" and
+///   "っきー", so that these syllabograms are translated from the spellings of the kana.
 fn generate_kana_to_romanji(csv_path: &Path, out_path: &Path) -> BuildResult {
     let mut reader = csv::Reader::from_path(csv_path)?;
     let mut file = BufWriter::new(File::create(out_path)?);
 
     let mut phf_map = phf_codegen::Map::new();
-    for line in reader.records() {
+    // The index of each record is that of its Kana, as generated by generate_kana.
+    writeln!(&mut file, "static TRANSLATIONS: &[KanaTranslation] = &[")?;
+    for (index, line) in reader.records().enumerate() {
         let record = line?;
-        assert_eq!(record.len(), 3);
-
-        let (kana, romanji) = (record[0].to_string(), record[1].to_string());
-        let alt_romanji = {
-            if record[2].is_empty() {
-                None
-            } else {
-                Some(record[2].to_string())
+        assert_eq!(record.len(), 11);
+
+        // Each romanization system column may contain multiple space separated spellings.
+        let spellings: Vec<Vec<String>> = (1..5)
+            .map(|column| record[column].split_whitespace().map(String::from).collect())
+            .collect();
+        let kana = &record[0];
+        writeln!(&mut file, "    {},", translation(index, kana, &spellings))?;
+        // Marks such as the sokuon and chōonpu themselves belong to no chart and are not joined.
+        if record[6].is_empty() {
+            continue
+        }
+
+        let is_hiragana = &record[5] == "hiragana";
+        let sokuon = match is_hiragana {
+            true => "っ",
+            false => "ッ",
+        };
+        let compounds = [
+            (format!("{}{}", sokuon, kana), true, false),
+            (format!("{}{}", kana, CHOONPU), false, true),
+            (format!("{}{}{}", sokuon, kana, CHOONPU), true, true),
+        ];
+        for (symbol, is_geminated, is_prolonged) in compounds {
+            let spellings: Vec<Vec<String>> = SYSTEMS.iter()
+                .zip(&spellings)
+                .map(|(&system, spellings)| {
+                    let mut spellings = spellings.clone();
+                    if is_geminated {
+                        spellings = spellings.iter().flat_map(|s| geminate(s, system)).collect();
+                    }
+                    if is_prolonged {
+                        spellings = spellings.iter().flat_map(|s| prolong(s, system, is_hiragana)).collect();
+                    }
+                    spellings
+                })
+                .collect();
+            let translation = translation(index, &symbol, &spellings);
+            phf_map.entry(symbol, &translation);
+        }
+    }
+    writeln!(&mut file, "];\n")?;
+    writeln!(
+        &mut file,
+        "static COMPOUND_TRANSLATIONS: phf::Map<&'static str, KanaTranslation> = {};\n",
+        phf_map.build()
+    )?;
+    Ok(())
+}
+
+/// The KanaTranslation of a syllabogram of the Kana with the given index,
+///   given its spellings within each of the SYSTEMS.
+fn translation(index: usize, symbol: &str, spellings: &[Vec<String>]) -> String {
+    let romanji = spellings[0].first()
+        .unwrap_or_else(|| panic!("kana has no Hepburn spelling: {}", symbol));
+    format!(
+        "KanaTranslation {{ kana: Kana({}), symbol: {:?}, romanji: {:?}, hepburn: &{:?}, kunrei: &{:?}, nihon: &{:?}, ime: &{:?} }}",
+        index,
+        symbol,
+        romanji,
+        spellings[0],
+        spellings[1],
+        spellings[2],
+        spellings[3]
+    )
+}
+
+/// The spellings of a kana preceded by a sokuon (っ) within the given system,
+///   i.e. "ki" becomes "kki" and Hepburn "chi" becomes "tchi".
+fn geminate(spelling: &str, system: System) -> Vec<String> {
+    let mut spellings = Vec::new();
+    if system == System::Hepburn && spelling.starts_with("ch") {
+        spellings.push(format!("t{}", spelling));
+    }
+    if let Some(first) = spelling.chars().next() {
+        spellings.push(format!("{}{}", first, spelling));
+    }
+    if system == System::Ime {
+        // A small tsu can also be typed on its own.
+        spellings.push(format!("xtu{}", spelling));
+        spellings.push(format!("ltu{}", spelling));
+    }
+    spellings
+}
+
+/// The spellings of a kana followed by a chōonpu (ー) within the given system,
+///   i.e. "ko" becomes "kō" in Hepburn, "kô" in Kunrei-shiki and Nihon-shiki, or is
+///   written out as "koo" or "kou". A long "e" is only written out as "ei" for hiragana,
+///   as it is the spelling of えい rather than of the katakana エー.
+fn prolong(spelling: &str, system: System, is_hiragana: bool) -> Vec<String> {
+    if system == System::Ime {
+        return vec![format!("{}-", spelling)]
+    }
+    let vowel = match spelling.chars().last() {
+        Some(vowel) if "aiueo".contains(vowel) => vowel,
+        _ => return vec![format!("{}-", spelling)],
+    };
+    let stem = &spelling[..spelling.len() - vowel.len_utf8()];
+    let accented = match (system, vowel) {
+        (System::Hepburn, 'a') => 'ā',
+        (System::Hepburn, 'i') => 'ī',
+        (System::Hepburn, 'u') => 'ū',
+        (System::Hepburn, 'e') => 'ē',
+        (System::Hepburn, _) => 'ō',
+        (_, 'a') => 'â',
+        (_, 'i') => 'î',
+        (_, 'u') => 'û',
+        (_, 'e') => 'ê',
+        (_, _) => 'ô',
+    };
+    let mut spellings = vec![format!("{}{}", stem, accented), format!("{}{}", spelling, vowel)];
+    match vowel {
+        'o' => spellings.push(format!("{}u", spelling)),
+        'e' if is_hiragana => spellings.push(format!("{}i", spelling)),
+        _ => {},
+    }
+    spellings
+}
+
+fn generate_kana(csv_path: &Path, out_path: &Path) -> BuildResult {
+    let mut reader = csv::Reader::from_path(csv_path)?;
+    let mut file = BufWriter::new(File::create(out_path)?);
+
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    let indices: HashMap<&str, usize> = records.iter()
+        .enumerate()
+        .map(|(index, record)| (&record[0], index))
+        .collect();
+    let kana = |symbol: &str| -> String {
+        let index = indices.get(symbol)
+            .unwrap_or_else(|| panic!("kana not found: {}", symbol));
+        format!("Kana({})", index)
+    };
+    let optional = |cell: &str, f: &dyn Fn(&str) -> String| -> String {
+        match cell {
+            "" => String::from("None"),
+            cell => format!("Some({})", f(cell)),
+        }
+    };
+
+    let mut phf_map = phf_codegen::Map::new();
+    writeln!(&mut file, "static KANA: &[KanaData] = &[")?;
+    for record in &records {
+        assert_eq!(record.len(), 11);
+        let syllabary = syllabary_variant(&record[5]);
+        let chart = optional(&record[6], &chart_variant);
+        let position = optional(&record[7], &|row| format!("({}, {})", row, &record[8]));
+        let base = optional(&record[9], &kana);
+        let yoon = optional(&record[10], &|yoon| {
+            let components: Vec<String> = yoon.split_whitespace().map(kana).collect();
+            assert_eq!(components.len(), 2, "yōon must have two components: {}", yoon);
+            format!("({}, {})", components[0], components[1])
+        });
+        writeln!(
+            &mut file,
+            "    KanaData {{ symbol: {:?}, syllabary: {}, chart: {}, position: {}, base: {}, yoon: {} }},",
+            &record[0],
+            syllabary,
+            chart,
+            position,
+            base,
+            yoon
+        )?;
+        phf_map.entry(record[0].to_string(), &kana(&record[0]));
+    }
+    writeln!(&mut file, "];\n")?;
+    writeln!(
+        &mut file,
+        "static SYMBOL_TO_KANA: phf::Map<&'static str, Kana> = {};\n",
+        phf_map.build()
+    )?;
+    Ok(())
+}
+
+fn generate_charts(csv_path: &Path, kana_csv_path: &Path, out_path: &Path) -> BuildResult {
+    // The index, syllabary and chart of each kana, where the index matches that of generate_kana.
+    let mut kana = HashMap::new();
+    for (index, line) in csv::Reader::from_path(kana_csv_path)?.records().enumerate() {
+        let record = line?;
+        kana.insert(record[0].to_string(), (index, record[5].to_string(), record[6].to_string()));
+    }
+
+    // The cells of each chart keyed by (syllabary, chart), in order of their first appearance.
+    let mut charts: Vec<((String, String), ChartCells)> = Vec::new();
+    for line in csv::Reader::from_path(csv_path)?.records() {
+        let record = line?;
+        assert_eq!(record.len(), 5);
+        let (syllabary, chart, symbol) = (&record[0], &record[1], &record[4]);
+        let position: (usize, usize) = (record[2].parse()?, record[3].parse()?);
+        if !symbol.is_empty() {
+            match kana.get(symbol) {
+                None => {
+                    return Err(format!("kana not found in {:?}: {}", kana_csv_path, symbol).into())
+                },
+                Some((_, s, c)) if (s.as_str(), c.as_str()) != (syllabary, chart) => {
+                    return Err(format!("kana {} is not within the {} {} chart", symbol, syllabary, chart).into())
+                },
+                Some(_) => {},
             }
+        }
+
+        let key = (syllabary.to_string(), chart.to_string());
+        let index = match charts.iter().position(|(k, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                charts.push((key, BTreeMap::new()));
+                charts.len() - 1
+            },
         };
-        phf_map.entry(
-            kana.clone(),
-            &format!(
-                "KanaTranslation {{ kana: {:?}, romanji: {:?}, alt_romanji: {:?} }}",
-                kana,
-                romanji,
-                alt_romanji
-            )
-        );
+        if charts[index].1.insert(position, symbol.to_string()).is_some() {
+            return Err(format!("cell {:?} of the {} {} chart is laid out twice", position, syllabary, chart).into())
+        }
+    }
+
+    for ((syllabary, chart), cells) in &charts {
+        let rows = cells.keys().map(|&(row, _)| row).max().unwrap_or(0) + 1;
+        let columns = cells.keys().map(|&(_, column)| column).max().unwrap_or(0) + 1;
+        if cells.len() != rows * columns {
+            return Err(format!(
+                "the {} {} chart is missing cells, every row must lay out all {} columns",
+                syllabary, chart, columns
+            ).into())
+        }
+    }
+    let mut missing: Vec<&str> = kana.iter()
+        .filter(|(_, (_, _, chart))| !chart.is_empty())
+        .filter(|(symbol, _)| !charts.iter().any(|(_, cells)| cells.values().any(|s| s == *symbol)))
+        .map(|(symbol, _)| symbol.as_str())
+        .collect();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        return Err(format!("kana missing from {:?}: {}", csv_path, missing.join(", ")).into())
+    }
+
+    let mut file = BufWriter::new(File::create(out_path)?);
+    let mut versions: Vec<&str> = Vec::new();
+    for ((_, chart), _) in &charts {
+        if !versions.contains(&chart.as_str()) {
+            versions.push(chart);
+        }
+    }
+    writeln!(&mut file, "#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Display, EnumIter)]")?;
+    writeln!(&mut file, "pub enum ChartVersion {{")?;
+    for version in versions {
+        writeln!(&mut file, "    {},", &chart_variant(version)["ChartVersion::".len()..])?;
+    }
+    writeln!(&mut file, "}}\n")?;
+
+    writeln!(&mut file, "static CHART_LAYOUTS: &[ChartLayout] = &[")?;
+    for ((syllabary, chart), cells) in &charts {
+        writeln!(
+            &mut file,
+            "    ChartLayout {{ syllabary: {}, chart: {}, rows: &[",
+            syllabary_variant(syllabary),
+            chart_variant(chart)
+        )?;
+        let mut rows: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for (&(row, _), symbol) in cells {
+            rows.entry(row).or_default().push(match kana.get(symbol) {
+                Some((index, _, _)) => format!("Some(Kana({}))", index),
+                None => String::from("None"),
+            });
+        }
+        for row in rows.values() {
+            writeln!(&mut file, "        &[{}],", row.join(", "))?;
+        }
+        writeln!(&mut file, "    ] }},")?;
+    }
+    writeln!(&mut file, "];")?;
+    Ok(())
+}
+
+/// The SyllabaryVersion variant of a syllabary within the data files.
+fn syllabary_variant(syllabary: &str) -> &'static str {
+    match syllabary {
+        "hiragana" => "SyllabaryVersion::Hirigana",
+        "katakana" => "SyllabaryVersion::Katakana",
+        syllabary => panic!("unknown syllabary: {}", syllabary),
+    }
+}
+
+/// The ChartVersion variant of a chart within the data files, i.e. "basic" is ChartVersion::Basic.
+fn chart_variant(chart: &str) -> String {
+    assert!(
+        chart.chars().all(|c| c.is_ascii_alphanumeric()) && chart.starts_with(|c: char| c.is_ascii_alphabetic()),
+        "chart names must be alphanumeric: {}", chart
+    );
+    format!("ChartVersion::{}{}", chart[..1].to_uppercase(), &chart[1..])
+}
+
+fn generate_words(csv_path: &Path, out_path: &Path) -> BuildResult {
+    let mut reader = csv::Reader::from_path(csv_path)?;
+    let mut file = BufWriter::new(File::create(out_path)?);
+
+    writeln!(&mut file, "static WORDS: &[Word] = &[")?;
+    for line in reader.records() {
+        let record = line?;
+        assert_eq!(record.len(), 2);
+        writeln!(
+            &mut file,
+            "    Word {{ kana: {:?}, meaning: {:?} }},",
+            &record[0],
+            &record[1]
+        )?;
+    }
+    writeln!(&mut file, "];")?;
+    Ok(())
+}
+
+/// Each SVG file is named by the hexadecimal code point of its kana (as is KanjiVG),
+///   and holds the strokes of the kana as `<path>` elements in the order they are written.
+fn generate_strokes(directory: &Path, kana_csv_path: &Path, out_path: &Path) -> BuildResult {
+    let mut known = Vec::new();
+    for line in csv::Reader::from_path(kana_csv_path)?.records() {
+        known.push(line?[0].to_string());
     }
+
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    let mut phf_map = phf_codegen::Map::new();
+    for path in entries.iter().filter(|path| path.extension().is_some_and(|e| e == "svg")) {
+        let kana = code_point_kana(path)?;
+        if !known.contains(&kana) {
+            return Err(format!("kana not found in {:?}: {}", kana_csv_path, kana).into())
+        }
+
+        let svg = fs::read_to_string(path)?;
+        let strokes: Vec<&str> = svg.split("<path").skip(1)
+            .map(|element| {
+                let start = element.find(" d=\"").map(|index| index + 4)?;
+                let end = element[start..].find('"')?;
+                Some(&element[start..start + end])
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| format!("stroke without path data in {:?}", path))?;
+        if strokes.is_empty() {
+            return Err(format!("no strokes found in {:?}", path).into())
+        }
+        phf_map.entry(kana, &format!("&{:?}", strokes));
+    }
+
+    let mut file = BufWriter::new(File::create(out_path)?);
     writeln!(
         &mut file,
-        "static KANA_TO_ROMANJI: phf::Map<&'static str, KanaTranslation> = {};\n",
+        "static STROKES: phf::Map<&'static str, &'static [&'static str]> = {};",
         phf_map.build()
     )?;
     Ok(())
 }
+
+/// Each audio clip is named by the hexadecimal code point of its kana, as are the stroke files,
+///   and is served from the audio directory alongside the app.
+fn generate_audio(directory: &Path, kana_csv_path: &Path, out_path: &Path) -> BuildResult {
+    let mut known = Vec::new();
+    for line in csv::Reader::from_path(kana_csv_path)?.records() {
+        known.push(line?[0].to_string());
+    }
+
+    // The audio directory is optional, in which case every kana falls back to speech synthesis.
+    let mut entries = match fs::read_dir(directory) {
+        Ok(entries) => entries.map(|entry| entry.map(|entry| entry.path())).collect::<Result<Vec<_>, _>>()?,
+        Err(_) => Vec::new(),
+    };
+    entries.sort();
+
+    let mut clips: BTreeMap<String, String> = BTreeMap::new();
+    let is_audio = |path: &Path| path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension));
+    for path in entries.iter().filter(|path| is_audio(path)) {
+        let kana = code_point_kana(path)?;
+        if !known.contains(&kana) {
+            return Err(format!("kana not found in {:?}: {}", kana_csv_path, kana).into())
+        }
+        let url = format!("{}/{}", directory.display(), path.file_name().unwrap_or_default().to_string_lossy());
+        if clips.insert(kana.clone(), url).is_some() {
+            return Err(format!("kana has more than one audio clip: {}", kana).into())
+        }
+    }
+
+    let mut phf_map = phf_codegen::Map::new();
+    for (kana, url) in &clips {
+        phf_map.entry(kana.as_str(), &format!("{:?}", url));
+    }
+    let mut file = BufWriter::new(File::create(out_path)?);
+    writeln!(
+        &mut file,
+        "static KANA_TO_AUDIO: phf::Map<&'static str, &'static str> = {};",
+        phf_map.build()
+    )?;
+    Ok(())
+}
+
+/// The kana of a file named by its hexadecimal code point, i.e. "03042.svg" is あ.
+fn code_point_kana(path: &Path) -> Result<String, String> {
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    u32::from_str_radix(stem, 16).ok()
+        .and_then(char::from_u32)
+        .map(String::from)
+        .ok_or_else(|| format!("file is not named by a code point: {:?}", path))
+}
+
+fn generate_confusables(csv_path: &Path, kana_csv_path: &Path, out_path: &Path) -> BuildResult {
+    // The index of each kana, which matches that of generate_kana.
+    let mut kana = HashMap::new();
+    for (index, line) in csv::Reader::from_path(kana_csv_path)?.records().enumerate() {
+        kana.insert(line?[0].to_string(), index);
+    }
+
+    let mut file = BufWriter::new(File::create(out_path)?);
+    writeln!(&mut file, "static CONFUSABLE_GROUPS: &[ConfusableGroup] = &[")?;
+    for line in csv::Reader::from_path(csv_path)?.records() {
+        let record = line?;
+        assert_eq!(record.len(), 2);
+        let members = record[0].split_whitespace()
+            .map(|symbol| match kana.get(symbol) {
+                Some(index) => Ok(format!("Kana({})", index)),
+                None => Err(format!("kana not found in {:?}: {}", kana_csv_path, symbol)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if members.len() < 2 {
+            return Err(format!("a confusable group must have at least two kana: {}", &record[0]).into())
+        }
+        let reason = match &record[1] {
+            "shape" => "Confusion::Shape",
+            "sound" => "Confusion::Sound",
+            reason => return Err(format!("unknown reason for confusion: {}", reason).into()),
+        };
+        writeln!(
+            &mut file,
+            "    ConfusableGroup {{ kana: &[{}], reason: {} }},",
+            members.join(", "),
+            reason
+        )?;
+    }
+    writeln!(&mut file, "];")?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! The pronunciation of kana.
+//!
+//! Kana with a bundled audio clip are played from it, while everything else (including
+//! the words of the Words mode) is spoken through the browser's speech synthesis.
+use yew::utils::window;
+use yew::web_sys::{HtmlAudioElement, SpeechSynthesisUtterance};
+
+include!("audio_codegen.rs");  // KANA_TO_AUDIO
+
+
+/// The URL of the bundled audio clip of the kana, if it has one.
+pub fn audio_clip(kana: &str) -> Option<&'static str> {
+    KANA_TO_AUDIO.get(kana).copied()
+}
+
+/// Pronounce the kana, interrupting anything which is still being pronounced.
+pub fn pronounce(kana: &str) {
+    let synthesis = window().speech_synthesis().ok();
+    if let Some(synthesis) = &synthesis {
+        synthesis.cancel();
+    }
+    if let Some(clip) = audio_clip(kana) {
+        if let Ok(audio) = HtmlAudioElement::new_with_src(clip) {
+            // Playback is refused until the player has interacted with the page.
+            audio.play().ok();
+            return
+        }
+    }
+    if let (Some(synthesis), Ok(utterance)) = (synthesis, SpeechSynthesisUtterance::new_with_text(kana)) {
+        utterance.set_lang("ja-JP");
+        synthesis.speak(&utterance);
+    }
+}
@@ -0,0 +1,26 @@
+//! This file is where the layouts of the kana charts are accessed.
+//!
+//! The ChartVersion enum and CHART_LAYOUTS table are generated by the build.rs script
+//! from the charts.csv file at the project root, and stored within the included
+//! charts_codegen.rs file. New charts can be added by laying out their cells within it.
+include!("charts_codegen.rs");  // ChartVersion, CHART_LAYOUTS
+
+use strum::{Display, EnumIter};
+
+use crate::kana::{Kana, SyllabaryVersion};
+
+/// A row of a chart, where empty cells are None.
+pub type ChartRow = &'static [Option<Kana>];
+
+struct ChartLayout {
+    syllabary: SyllabaryVersion,
+    chart: ChartVersion,
+    rows: &'static [ChartRow],
+}
+
+/// The rows of the given chart, which are empty if the syllabary has no such chart.
+pub fn chart_layout(chart: ChartVersion, syllabary: SyllabaryVersion) -> &'static [ChartRow] {
+    CHART_LAYOUTS.iter()
+        .find(|layout| layout.chart == chart && layout.syllabary == syllabary)
+        .map_or(&[], |layout| layout.rows)
+}
@@ -1,31 +1,76 @@
 //! The main component of the application.
 use std::rc::Rc;
 
+use rand::Rng;
 use yew::prelude::*;
-use yew::utils::window;
+use yew::services::keyboard::{KeyListenerHandle, KeyboardService};
+use yew::utils::{document, window};
+use yewtil::NeqAssign;
 
-use crate::translations::get_translations;
+use crate::{
+    kana::Kana,
+    statistics::StatisticsHandle,
+    utils::{prefers_reduced_motion, some_if, EventListener},
+};
 use super::{
-    game::GameScreen,
+    game::{GameScreen, GameSettings},
     kana::KanaSelector,
     hide_state::HideState,
     icons::github,
+    keymap::{move_focus, render_help, shortcut, toggle_focused, Shortcut},
+    review::ReviewScreen,
+    settings::SettingsPanel,
+    share::{read_fragment, read_seed, write_fragment},
+    statistics::StatisticsScreen,
 };
 
+/// The bound of the seeds chosen at random, which are kept short so that
+///   they are easily read out and typed into a link.
+const SEED_LIMIT: u64 = 1_000_000;
+
 
 pub enum AppMessage {
     AnimationStart(Animation, Screen),
     AnimationEnd(Animation, Screen),
-    ToggleGameMode,
-    SetSymbols(Vec<String>)
+    ShowScreen(Screen),
+    /// Leave the game, reviewing the session if any answers were given.
+    LeaveGame,
+    /// Start a new game with only the given kana.
+    Drill(Vec<Kana>),
+    SetSettings(GameSettings),
+    SetKana(Vec<Kana>),
+    /// A key press which is mapped to a shortcut by the keymap.
+    Shortcut(Shortcut, KeyboardEvent),
+    SetHelp(bool),
+    SetPaused(bool),
+    /// The page has been hidden or lost focus, which pauses any game in progress.
+    Hidden,
 }
 
 pub struct App {
     link: ComponentLink<Self>,
+    screen: Screen,
     game_hide_state: HideState,
     kana_hide_state: HideState,
-    symbols: Vec<String>
-
+    review_hide_state: HideState,
+    statistics_hide_state: HideState,
+    settings: GameSettings,
+    statistics: StatisticsHandle,
+    kana: Vec<Kana>,
+    /// The kana being drilled, which are played in place of those selected.
+    drill: Option<Vec<Kana>>,
+    /// The kana selection given by the link the app was opened with, if any.
+    shared_kana: Option<Rc<Vec<Kana>>>,
+    /// The seed given by the link the app was opened with, which every game is played with.
+    shared_seed: Option<u64>,
+    /// The seed of the current game.
+    seed: u64,
+    /// Whether the overlay listing the keyboard shortcuts is shown.
+    help: bool,
+    paused: bool,
+    _keydown: KeyListenerHandle,
+    _onblur: EventListener,
+    _onvisibilitychange: EventListener,
 }
 
 impl Component for App {
@@ -33,11 +78,42 @@ impl Component for App {
     type Properties = ();
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let _keydown = KeyboardService::register_key_down(
+            &document(),
+            link.batch_callback(|event: KeyboardEvent| {
+                shortcut(&event).map(|shortcut| AppMessage::Shortcut(shortcut, event))
+            }),
+        );
+        let _onblur = EventListener::new(&window(), "blur", link.callback(|_| AppMessage::Hidden));
+        let _onvisibilitychange = EventListener::new(
+            &document(),
+            "visibilitychange",
+            link.batch_callback(|_| some_if!(document().hidden(), AppMessage::Hidden)),
+        );
+        let shared = read_fragment();
+        let (settings, shared_kana) = match shared {
+            Some(shared) => (shared.settings, Some(Rc::new(shared.kana))),
+            None => (GameSettings::default(), None),
+        };
         Self {
             link,
+            screen: Screen::KanaSelector,
             game_hide_state: HideState::new_hidden(),
             kana_hide_state: HideState::new_visible(),
-            symbols: Vec::new()
+            review_hide_state: HideState::new_hidden(),
+            statistics_hide_state: HideState::new_hidden(),
+            settings,
+            statistics: StatisticsHandle::load(),
+            kana: Vec::new(),
+            drill: None,
+            shared_kana,
+            shared_seed: read_seed(),
+            seed: 0,
+            help: false,
+            paused: false,
+            _keydown,
+            _onblur,
+            _onvisibilitychange,
         }
     }
 
@@ -48,45 +124,110 @@ impl Component for App {
             },
             AppMessage::AnimationEnd(animation, child) => {
                 // At the end for a FadeOut animation we toggle the display attribute
-                //   of the game and kana screens.
-                match (animation, child) {
-                    (Animation::FadeOut, Screen::KanaSelector) => {
-                        self.game_hide_state.toggle_display_none();
-                        self.kana_hide_state.toggle_display_none();
-                        true
-                    },
-                    (Animation::FadeOut, Screen::Game) => {
-                        self.game_hide_state.toggle_display_none();
-                        self.kana_hide_state.toggle_display_none();
+                //   of the screen which faded out and the screen being shown.
+                match animation {
+                    Animation::FadeOut => {
+                        self.hide_state_mut(child).toggle_display_none();
+                        self.hide_state_mut(self.screen).toggle_display_none();
                         true
                     },
                     _ => false
                 }
             },
-            AppMessage::ToggleGameMode => {
+            AppMessage::ShowScreen(screen) => {
+                if screen == self.screen {
+                    return false
+                }
                 window().scroll_to_with_x_and_y(0.0, 0.0);
-                self.game_hide_state.toggle();
-                self.kana_hide_state.toggle();
+                match screen {
+                    // The previous session has been reviewed, if it was going to be.
+                    Screen::Game => {
+                        self.statistics.borrow_mut().end_session();
+                        self.seed = self.shared_seed.unwrap_or_else(|| rand::thread_rng().gen_range(0..SEED_LIMIT));
+                    },
+                    Screen::KanaSelector => self.drill = None,
+                    _ => {},
+                }
+                let previous = self.screen;
+                self.hide_state_mut(previous).toggle();
+                self.hide_state_mut(screen).toggle();
+                self.screen = screen;
+                self.paused = false;
+                // Without the fade animations there is no FadeOut to wait for.
+                if prefers_reduced_motion() {
+                    self.update(AppMessage::AnimationEnd(Animation::FadeOut, previous));
+                }
                 true
             },
-            AppMessage::SetSymbols(symbols) => {
-                self.symbols = symbols;
+            AppMessage::LeaveGame => {
+                let screen = match self.statistics.borrow().session_answers().is_empty() {
+                    true => Screen::KanaSelector,
+                    false => Screen::Review,
+                };
+                self.update(AppMessage::ShowScreen(screen))
+            },
+            AppMessage::Drill(kana) => {
+                self.drill = Some(kana);
+                self.update(AppMessage::ShowScreen(Screen::Game))
+            },
+            AppMessage::SetSettings(settings) => {
+                self.settings = settings;
+                write_fragment(&self.kana, &self.settings, self.shared_seed);
+                true
+            },
+            AppMessage::SetKana(kana) => {
+                self.kana = kana;
+                write_fragment(&self.kana, &self.settings, self.shared_seed);
                 false
-            }
+            },
+            AppMessage::Shortcut(shortcut, event) => {
+                let should_render = match (shortcut, self.screen) {
+                    (Shortcut::Help, _) => self.update(AppMessage::SetHelp(!self.help)),
+                    (Shortcut::Pause, _) if self.help => self.update(AppMessage::SetHelp(false)),
+                    // The help overlay covers the screen, so no other shortcut applies.
+                    (_, _) if self.help => return false,
+                    (Shortcut::Pause, Screen::Game) => self.update(AppMessage::SetPaused(!self.paused)),
+                    (Shortcut::Confirm, Screen::KanaSelector) => self.update(AppMessage::ShowScreen(Screen::Game)),
+                    (Shortcut::Confirm, Screen::Game) if self.paused => self.update(AppMessage::LeaveGame),
+                    (Shortcut::Confirm, Screen::Review) => self.update(AppMessage::ShowScreen(Screen::KanaSelector)),
+                    (Shortcut::Focus(direction), Screen::KanaSelector) => {
+                        move_focus(direction);
+                        false
+                    },
+                    // Without a focused button, Space is left to scroll the page.
+                    (Shortcut::Toggle, Screen::KanaSelector) if toggle_focused() => false,
+                    _ => return false,
+                };
+                event.prevent_default();
+                should_render
+            },
+            AppMessage::SetHelp(help) => {
+                self.help.neq_assign(help)
+            },
+            AppMessage::SetPaused(paused) => {
+                self.paused.neq_assign(paused)
+            },
+            AppMessage::Hidden => {
+                match self.screen {
+                    Screen::Game => self.update(AppMessage::SetPaused(true)),
+                    _ => false,
+                }
+            },
         }
     }
 
     fn change(&mut self, _props: Self::Properties) -> ShouldRender { false }
 
     fn view(&self) -> Html {
-        let onclick = self.link.callback(|_| AppMessage::ToggleGameMode);
-        let mut button_content = "Start";
-        let mut translations = Vec::new();
+        let mut kana = Vec::new();
         if self.is_play_mode() {
-            button_content = "Back";
-            translations = get_translations(&self.symbols);
+            kana = self.drill.clone().unwrap_or_else(|| self.kana.clone());
         }
 
+        let help = match self.help {
+            true => render_help(self.link.callback(|_| AppMessage::SetHelp(false))),
+            false => html! {},
+        };
         return html! {
             <>
             <div class="github-link">
@@ -95,16 +236,32 @@ impl Component for App {
             <div class="main-container" centered=(!self.game_hide_state.is_hidden()).to_string()>
                 <h2 class="title">{ "Kana Kilo" }</h2>
                 <div class="play-configurations">
-                    <button href="#top" onclick=onclick>{ button_content }</button>
+                    { self.render_navigation() }
                 </div>
                 <GameScreen
                     hide_state=self.game_hide_state.clone()
-                    translations=Rc::new(translations)
-                    screen_type=Screen::Game/>
+                    kana=Rc::new(kana)
+                    paused=self.paused
+                    screen_type=Screen::Game
+                    seed=self.seed
+                    shared_seed=self.shared_seed.is_some()
+                    settings=self.settings.clone()
+                    statistics=self.statistics.clone()/>
                 <KanaSelector
                     hide_state=self.kana_hide_state.clone()
-                    screen_type=Screen::KanaSelector/>
+                    screen_type=Screen::KanaSelector
+                    selection=self.shared_kana.clone()/>
+                <ReviewScreen
+                    hide_state=self.review_hide_state.clone()
+                    screen_type=Screen::Review
+                    seed=self.seed
+                    statistics=self.statistics.clone()/>
+                <StatisticsScreen
+                    hide_state=self.statistics_hide_state.clone()
+                    screen_type=Screen::Statistics
+                    statistics=self.statistics.clone()/>
             </div>
+            { help }
             </>
         }
     }
@@ -114,6 +271,42 @@ impl App {
     fn is_play_mode(&self) -> bool {
         !self.game_hide_state.is_hidden()
     }
+
+    fn hide_state_mut(&mut self, screen: Screen) -> &mut HideState {
+        match screen {
+            Screen::Game => &mut self.game_hide_state,
+            Screen::KanaSelector => &mut self.kana_hide_state,
+            Screen::Review => &mut self.review_hide_state,
+            Screen::Statistics => &mut self.statistics_hide_state,
+        }
+    }
+
+    /// Render the buttons for navigating from the current screen to the others.
+    fn render_navigation(&self) -> Html {
+        let button = |content: &str, screen: Screen| {
+            let onclick = self.link.callback(move |_| AppMessage::ShowScreen(screen));
+            html! { <button href="#top" onclick=onclick>{ content }</button> }
+        };
+        match self.screen {
+            Screen::KanaSelector => html! {
+                <>
+                { button("Start", Screen::Game) }
+                { button("Statistics", Screen::Statistics) }
+                <button onclick=self.link.callback(|_| AppMessage::SetHelp(true)) title="Keyboard Shortcuts">{ "?" }</button>
+                <SettingsPanel
+                    settings=self.settings.clone()
+                    onchange=self.link.callback(AppMessage::SetSettings)/>
+                </>
+            },
+            Screen::Game => html! {
+                <>
+                <button href="#top" onclick=self.link.callback(|_| AppMessage::LeaveGame)>{ "Back" }</button>
+                <button onclick=self.link.callback(|_| AppMessage::SetPaused(true))>{ "Pause" }</button>
+                </>
+            },
+            Screen::Review | Screen::Statistics => button("Back", Screen::KanaSelector),
+        }
+    }
 }
 
 
@@ -144,6 +337,8 @@ impl From<String> for Animation {
 pub enum Screen {
     Game,
     KanaSelector,
+    Review,
+    Statistics,
 }
 
 impl Screen {
@@ -0,0 +1,41 @@
+//! A minimal HSL color used for shading elements by performance.
+pub const RED: Color = Color { h: 6, s: 78, l: 57 };
+pub const GREEN: Color = Color { h: 145, s: 70, l: 50 };
+pub const ORANGE: Color = Color { h: 28, s: 80, l: 52 };
+pub const YELLOW: Color = Color { h: 45, s: 90, l: 55 };
+
+
+pub struct Color {
+    pub h: u8,
+    pub s: u8,
+    pub l: u8,
+}
+
+impl Color {
+    pub fn as_string(&self) -> String {
+        format!("hsl({},{}%,{}%)", self.h, self.s, self.l)
+    }
+
+    pub fn interpolate_to(&self, other: &Self, percent: f32) -> Color {
+        Color {
+            h: interpolate(&self.h, &other.h, &percent),
+            s: interpolate(&self.s, &other.s, &percent),
+            l: interpolate(&self.l, &other.l, &percent),
+        }
+    }
+
+    /// Interpolate the lightness of the color towards the given lightness.
+    pub fn shade_to(&self, lightness: u8, percent: f32) -> Color {
+        Color {
+            h: self.h,
+            s: self.s,
+            l: interpolate(&self.l, &lightness, &percent),
+        }
+    }
+}
+
+fn interpolate(from: &u8, to: &u8, percent: &f32) -> u8 {
+    let from = *from as f32;
+    let to = *to as f32;
+    (from + (percent * (to - from))) as u8
+}
@@ -4,6 +4,8 @@ use yew::prelude::*;
 use yew::services::interval::{IntervalService, IntervalTask};
 use yewtil::NeqAssign;
 
+use super::{GameMessage, GameScreen};
+
 
 pub enum ClockMessage {
     Tick
@@ -13,11 +15,17 @@ pub enum ClockMessage {
 pub struct ClockProperties {
     #[prop_or_default]
     pub active: bool,
+    /// The number of seconds to count down from. The clock counts up if there are none.
+    #[prop_or_default]
+    pub countdown: Option<usize>,
+    #[prop_or_default]
+    pub paused: bool,
 }
 
 pub struct Clock {
-    active: bool,
+    props: ClockProperties,
     seconds: usize,
+    ontimeout: Callback<()>,
     _handler: IntervalTask,
 }
 
@@ -26,25 +34,38 @@ impl Component for Clock {
     type Properties = ClockProperties;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ontimeout = {
+            let parent = link
+                .get_parent().expect("Clock component does not have parent")
+                .clone().downcast::<GameScreen>();
+            parent.callback(|_| GameMessage::TimeUp)
+        };
         let _handler = IntervalService::spawn(
             Duration::from_secs(1),
             link.callback(|_| Self::Message::Tick)
         );
-        Self { active: props.active, seconds: 0, _handler }
+        Self { props, seconds: 0, ontimeout, _handler }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Self::Message::Tick => {
+                if !self.props.active || self.props.paused || self.remaining() == Some(0) {
+                    return false
+                }
                 self.seconds += 1;
-                self.active
+                if self.remaining() == Some(0) {
+                    self.ontimeout.emit(());
+                }
+                true
             }
         }
     }
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
-        let should_render = self.active.neq_assign(props.active);
-        if should_render {
+        let reset = self.props.active != props.active || self.props.countdown != props.countdown;
+        let should_render = self.props.neq_assign(props);
+        if reset {
             self.seconds = 0;
         }
         should_render
@@ -52,9 +73,8 @@ impl Component for Clock {
 
     fn view(&self) -> Html {
         let time = {
-            let minutes = self.seconds / 60;
-            let seconds = self.seconds % 60;
-            format!("{:02}:{:02}", minutes, seconds)
+            let seconds = self.remaining().unwrap_or(self.seconds);
+            format!("{:02}:{:02}", seconds / 60, seconds % 60)
         };
         return html!{
             <div class="clock">
@@ -64,3 +84,10 @@ impl Component for Clock {
         }
     }
 }
+
+impl Clock {
+    /// The number of seconds remaining in the countdown.
+    fn remaining(&self) -> Option<usize> {
+        self.props.countdown.map(|countdown| countdown.saturating_sub(self.seconds))
+    }
+}
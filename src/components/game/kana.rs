@@ -1,15 +1,45 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use yew::prelude::*;
 use yew::services::ConsoleService;
 use yew::virtual_dom::VNode;
 use yewtil::NeqAssign;
 
-use super::{GameMessage, GameScreen, KanaTranslationList};
+use crate::{
+    audio::pronounce,
+    confusables::{confusables, mistaken_for},
+    grading::{match_slip, Grade},
+    kana::Kana,
+    matcher::{Match, SpellingTrie},
+    romanization::RomanizationSystems,
+    scheduler::{ConfusablesScheduler, Scheduler, SchedulerStrategy},
+    statistics::StatisticsHandle,
+    translations::{to_hiragana, KanaTranslation},
+    utils::{now, prefers_reduced_motion},
+    words::{get_words, Word},
+};
+use super::{GameMessage, GameMode, GameScreen, GameSettings, KanaTranslationList};
+
+/// The least number of options given for a multiple choice content.
+const MIN_CHOICES: usize = 4;
+/// The most number of options given for a multiple choice content,
+///   which are only exceeded the minimum by kana with many confusables.
+const MAX_CHOICES: usize = 6;
 
 
 #[derive(Clone, Properties)]
 pub struct KanaLineProperties {
     pub hidden: bool,
     pub length: usize,
+    /// The seed of the round, which regenerates the contents whenever it changes.
+    #[prop_or_default]
+    pub seed: u64,
+    /// Whether the seed was given by the link, in which case every player is drilled identically.
+    #[prop_or_default]
+    pub shared_seed: bool,
+    pub settings: GameSettings,
+    pub statistics: StatisticsHandle,
+    /// Whether the player has explicitly submitted the current text as their answer.
+    pub submitted: bool,
     pub text: String,
     pub translations: KanaTranslationList,
 }
@@ -19,18 +49,52 @@ impl NeqAssign<KanaLineProperties> for KanaLineProperties {
         let should_render =
             self.hidden != new.hidden
             || self.length != new.length
+            || self.seed != new.seed
+            || self.shared_seed != new.shared_seed
+            || self.settings != new.settings
             || self.translations != new.translations;
         *self = new;
         should_render
     }
 }
 
+/// The result of answering a single kana.
+pub struct Answer {
+    pub translation: &'static KanaTranslation,
+    /// The text typed for this kana, which is empty for a handwritten answer.
+    pub text: String,
+    pub grade: Grade,
+    /// The number of milliseconds between the kana becoming current and being answered.
+    pub response_time: f64,
+}
+
+impl Answer {
+    /// The sentence read out by screen readers once the kana has been answered.
+    pub fn announcement(&self) -> String {
+        let translation = self.translation;
+        let answered = match self.text.is_empty() {
+            true => String::new(),
+            false => format!(" You answered {}.", self.text),
+        };
+        match self.grade {
+            Grade::Correct => format!("Correct, {} is {}.", translation.symbol, translation.romanji),
+            Grade::NearMiss => format!("Nearly, {} is {}.{}", translation.symbol, translation.romanji, answered),
+            Grade::Wrong => format!("Incorrect, {} is {}.{}", translation.symbol, translation.romanji, answered),
+        }
+    }
+}
+
 pub struct KanaLine {
     props: KanaLineProperties,
     content_generator: ContentGenerator,
     contents: Vec<Content>,
+    current_since: f64,
     index: usize,
-    onshift: Callback<bool>,
+    /// Hands the text typed beyond an answer to the GameScreen, as the start of the next answer.
+    oncarry: Callback<String>,
+    onchoice: Callback<String>,
+    onreplay: Callback<MouseEvent>,
+    onshift: Callback<Vec<Answer>>,
     shifted: bool,
 }
 
@@ -40,23 +104,31 @@ impl Component for KanaLine {
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         let contents = Vec::with_capacity(props.length);
-        let onshift = {
-            let parent = link
-                .get_parent().expect("KanaLine component does not have parent")
-                .clone().downcast::<GameScreen>();
-            parent.callback(GameMessage::KanaShift)
-        };
+        let parent = link
+            .get_parent().expect("KanaLine component does not have parent")
+            .clone().downcast::<GameScreen>();
+        let onshift = parent.callback(GameMessage::KanaShift);
+        let oncarry = parent.callback(GameMessage::CarryOver);
+        // Picking an option is an explicit submission, just as with the KanaKeyboard.
+        let onchoice = parent.callback(GameMessage::KeyboardInput);
+        // Clicking the KanaLine pronounces the current kana again.
+        let onreplay = link.callback(|_| ());
         Self {
             props,
             content_generator: ContentGenerator::default(),
             contents,
+            current_since: 0.0,
             index: 0,
+            oncarry,
+            onchoice,
+            onreplay,
             onshift,
             shifted: false,
         }
     }
 
     fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        self.pronounce_current();
         false
     }
 
@@ -65,20 +137,27 @@ impl Component for KanaLine {
 
         // If the properties have changed, then we should reinitialize the KanaLine contents.
         if should_render {
+            // Strategies steered by the answers of the player would drill players sharing a seed differently.
+            let strategy = match self.props.shared_seed {
+                true => SchedulerStrategy::Uniform,
+                false => self.props.settings.mode.strategy(),
+            };
+            self.content_generator.restart(
+                strategy,
+                &self.props.statistics,
+                self.props.seed,
+            );
             self.content_generator.set_translations(self.props.translations.clone());
             self.contents.clear();
             if !self.props.hidden {
-                for _ in 0..self.props.length {
-                    match self.content_generator.random() {
-                        Some(content) => self.contents.push(content),
-                        None => {
-                            ConsoleService::warn("No Content Generated!");
-                            break
-                        }
-                    };
+                self.contents = self.content_generator.fill(self.props.length, &self.props.settings);
+                if self.contents.len() < self.props.length {
+                    ConsoleService::warn("No Content Generated!");
                 }
             }
             self.index = 0;
+            self.current_since = now();
+            self.pronounce_current();
         }
 
         should_render | self.check()
@@ -89,17 +168,20 @@ impl Component for KanaLine {
             format!("margin-left: -{}em;", self.get_margin()),
             format!("width: {}em;", self.get_width()),
         ];
-        if self.shifted {
+        if self.shifted && !prefers_reduced_motion() {
             styles.push(String::from("transition: margin-left 200ms linear"))
         }
         return html!{
-            <div class="kana-line">
-                <div class="kana-line-container" >
-                    <div class="kana-line-scroll-container" style=styles.join(" ")>
-                        { for self.contents.iter().map(|c| c.render() )}
+            <>
+                <div class="kana-line" onclick=self.onreplay.clone()>
+                    <div class="kana-line-container" >
+                        <div class="kana-line-scroll-container" style=styles.join(" ")>
+                            { for self.contents.iter().map(|c| c.render() )}
+                        </div>
                     </div>
                 </div>
-            </div>
+                { self.render_choices() }
+            </>
         }
     }
 }
@@ -111,35 +193,72 @@ impl KanaLine {
             return false
         }
 
+        let text = self.props.text.to_lowercase();
         let current = self.contents.get_mut(self.index)
             .expect("could not locate current content of KanaLine");
-        current.check(self.props.text.to_lowercase());
-        match current.state {
+        let consumed = current.check(&text, self.props.submitted, &self.props.settings);
+        let results = match &current.state {
             ContentState::Unanswered => {
                 self.shifted = false;
-                self.resample()
-            },
-            ContentState::Answered(is_correct) => {
-                self.onshift.emit(is_correct);
-                self.index += 1;
-                self.shifted = true;
-                true
+                return self.resample()
             },
+            ContentState::Answered(results) => results.clone(),
+        };
+
+        // The response time of a word is divided evenly amongst its syllabograms.
+        let answered_at = now();
+        let response_time = (answered_at - self.current_since) / results.len() as f64;
+        let mut answers = Vec::with_capacity(results.len());
+        for (&translation, (text, grade)) in current.translations.iter().zip(results) {
+            // A near-miss shows that the kana is known, so the scheduler treats it as recalled.
+            self.content_generator.record(translation, !grade.is_wrong());
+            if grade.is_wrong() {
+                if let Some(other) = mistaken_for(translation.kana, &text) {
+                    self.content_generator.record_confusion(translation.kana, other);
+                }
+            }
+            answers.push(Answer { translation, text, grade, response_time });
         }
+        self.onshift.emit(answers);
+        let remainder = &text[consumed..];
+        if !remainder.is_empty() {
+            self.oncarry.emit(remainder.to_string());
+        }
+        self.current_since = answered_at;
+        self.index += 1;
+        self.shifted = true;
+        self.pronounce_current();
+        true
     }
 
-    fn resample(&mut self) -> ShouldRender {
-        if !self.contents.is_empty() && self.index >= (self.contents.len() / 2) {
-            for _ in 0..(self.contents.len() / 3) {
-                self.contents.remove(0);
-                let new = self.content_generator.random()
-                    .expect("Could not generate new content during resample. ");
-                self.contents.push(new);
-                self.index -= 1;
-            }
-            return true
+    /// The options of the current multiple choice content.
+    fn render_choices(&self) -> Html {
+        let choices = match self.contents.get(self.index) {
+            Some(current) if !current.choices.is_empty() => &current.choices,
+            _ => return html! {},
+        };
+        return html! {
+            <div class="choice-line">
+                { for choices.iter().map(|&choice| {
+                    let onclick = self.onchoice.reform(move |_| choice.to_string());
+                    html! { <button onclick=onclick>{ choice }</button> }
+                }) }
+            </div>
         }
-        false
+    }
+
+    fn pronounce_current(&self) {
+        if self.props.hidden || !self.props.settings.is_pronounced() {
+            return
+        }
+        if let Some(current) = self.contents.get(self.index) {
+            pronounce(&current.kana());
+        }
+    }
+
+    fn resample(&mut self) -> ShouldRender {
+        let index = self.content_generator.resample(&mut self.contents, self.index, &self.props.settings);
+        self.index.neq_assign(index)
     }
 
     fn get_margin(&self) -> f32 {
@@ -162,89 +281,430 @@ impl KanaLine {
     }
 }
 
-#[derive(Default)]
 struct ContentGenerator {
+    rng: StdRng,
+    scheduler: Box<dyn Scheduler>,
     translations: KanaTranslationList,
+    words: Vec<(&'static Word, Vec<&'static KanaTranslation>)>,
+}
+
+impl Default for ContentGenerator {
+    fn default() -> Self {
+        Self::new(SchedulerStrategy::default(), 0)
+    }
 }
 
 impl ContentGenerator {
-    fn random(&self) -> Option<Content> {
-        if self.translations.is_empty() {
-            return None
+    fn new(strategy: SchedulerStrategy, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            scheduler: strategy.build(),
+            translations: KanaTranslationList::default(),
+            words: Vec::new(),
         }
-        let translation = unsafe {
-            let index = rand::random::<usize>() % self.translations.len();
-            <&crate::KanaTranslation>::clone(self.translations.get_unchecked(index))
-        };
+    }
+
+    /// Generate the given number of contents, or fewer should the contents run out.
+    fn fill(&mut self, length: usize, settings: &GameSettings) -> Vec<Content> {
+        std::iter::from_fn(|| self.next(settings)).take(length).collect()
+    }
+
+    /// Once the current content is past the middle of the contents, replace the oldest third
+    ///   of them with newly generated contents. Returns the index of the current content afterwards.
+    fn resample(&mut self, contents: &mut Vec<Content>, index: usize, settings: &GameSettings) -> usize {
+        if contents.is_empty() || index < contents.len() / 2 {
+            return index
+        }
+        let count = contents.len() / 3;
+        contents.drain(..count);
+        for _ in 0..count {
+            let new = self.next(settings)
+                .expect("Could not generate new content during resample. ");
+            contents.push(new);
+        }
+        index - count
+    }
+
+    fn next(&mut self, settings: &GameSettings) -> Option<Content> {
+        let translation = self.scheduler.next(&self.translations, &mut self.rng)?;
+        let mode = settings.mode;
+        match mode {
+            GameMode::Words => {
+                // Words containing the kana chosen by the scheduler are preferred,
+                //   so that the weighting of the scheduler carries over to the words.
+                let mut candidates: Vec<_> = self.words.iter()
+                    .filter(|(_, translations)| translations.contains(&translation))
+                    .collect();
+                if candidates.is_empty() {
+                    candidates = self.words.iter().collect();
+                }
+                if candidates.is_empty() {
+                    return None
+                }
+                let (word, translations) = candidates[self.rng.gen_range(0..candidates.len())];
+                Some(Content::new(translations.clone(), mode, Some(word.meaning)))
+            },
+            GameMode::Choice => {
+                let mut content = Content::new(vec![translation], mode, None);
+                content.choices = self.choices(translation, settings);
+                Some(content)
+            },
+            _ => Some(Content::new(vec![translation], mode, None)),
+        }
+    }
+
+    /// The romanji options of a multiple choice content, which are the answer along with
+    ///   the kana it is most easily confused with, topped up with other kana.
+    fn choices(&mut self, translation: &'static KanaTranslation, settings: &GameSettings) -> Vec<&'static str> {
+        let spelling = |t: &KanaTranslation| t.accepted_spellings(settings.romanization)[0];
+        let rng = &mut self.rng;
+        let confusable: Vec<&'static KanaTranslation> = confusables(translation.kana)
+            .into_iter()
+            .map(|(kana, _)| kana.translation())
+            .collect();
+        // Other kana are drawn from those in play before the rest of the syllabary.
+        let mut in_play: Vec<&'static KanaTranslation> = self.translations.to_vec();
+        in_play.shuffle(rng);
+        let syllabary = translation.kana.syllabary();
+        let mut rest: Vec<&'static KanaTranslation> = Kana::iter()
+            .filter(|kana| kana.chart().is_some() && kana.syllabary() == syllabary)
+            .map(Kana::translation)
+            .collect();
+        rest.shuffle(rng);
+
+        let mut choices = vec![spelling(translation)];
+        let candidates = confusable.iter().map(|t| (t, MAX_CHOICES))
+            .chain(in_play.iter().chain(&rest).map(|t| (t, MIN_CHOICES)));
+        for (candidate, limit) in candidates {
+            let candidate = spelling(candidate);
+            if choices.len() < limit && !choices.contains(&candidate) {
+                choices.push(candidate);
+            }
+        }
+        choices.shuffle(rng);
+        choices
+    }
+
+    fn record(&mut self, translation: &'static KanaTranslation, is_correct: bool) {
+        self.scheduler.record(translation, is_correct);
+    }
+
+    fn record_confusion(&mut self, kana: Kana, mistaken_for: Kana) {
+        self.scheduler.record_confusion(kana, mistaken_for);
+    }
 
-        Some(Content::new(translation))
+    /// Start afresh with the given scheduling strategy and seed, so that the contents
+    ///   generated are reproducible from the seed. The confusables scheduler also learns
+    ///   of the mistakes made since it was last built.
+    fn restart(&mut self, strategy: SchedulerStrategy, statistics: &StatisticsHandle, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.scheduler = match strategy {
+            SchedulerStrategy::Confusables => {
+                Box::new(ConfusablesScheduler::new(&statistics.borrow().confusion_matrix()))
+            },
+            strategy => strategy.build(),
+        };
     }
 
     fn set_translations(&mut self, translations: KanaTranslationList) {
+        self.words = get_words(&translations);
         self.translations = translations;
     }
 }
 
-
 enum ContentState {
     Unanswered,
-    Answered(bool),
+    /// The text typed for, and the grade of, each syllabogram of the content.
+    Answered(Vec<(String, Grade)>),
 }
 
+/// A single kana, or a word made up of multiple syllabograms, within the KanaLine.
 struct Content {
-    translation: &'static crate::KanaTranslation,
+    translations: Vec<&'static KanaTranslation>,
+    meaning: Option<&'static str>,
+    /// The romanji options of a multiple choice content.
+    choices: Vec<&'static str>,
+    mode: GameMode,
     size: usize,
     state: ContentState,
 }
 
 impl Content {
-    fn new(translation: &'static crate::KanaTranslation) -> Self {
-        let size = translation.kana.chars().count();
+    fn new(
+        translations: Vec<&'static KanaTranslation>,
+        mode: GameMode,
+        meaning: Option<&'static str>,
+    ) -> Self {
+        let size = match mode {
+            GameMode::Production => {
+                // Romanji characters are roughly half the width of the kana.
+                translations.iter().map(|t| t.romanji.len()).sum::<usize>().div_ceil(2)
+            },
+            _ => translations.iter().map(|t| t.symbol.chars().count()).sum(),
+        };
         Self {
-            translation,
+            translations,
+            meaning,
+            choices: Vec::new(),
+            mode,
             size,
             state: ContentState::Unanswered,
         }
     }
 
-    fn check(&mut self, text: String) {
+    fn kana(&self) -> String {
+        self.translations.iter().map(|t| t.symbol).collect()
+    }
+
+    fn romanji(&self) -> String {
+        self.translations.iter().map(|t| t.romanji).collect()
+    }
+
+    /// Grade the text if it answers the content, returning the length of the text
+    ///   which the answer is made up of. Any text beyond it begins the next answer.
+    fn check(&mut self, text: &str, submitted: bool, settings: &GameSettings) -> usize {
         if text.is_empty() {
-            return
+            return 0
+        }
+        match self.mode {
+            GameMode::Production => self.check_kana(text, submitted, settings),
+            GameMode::Choice => self.check_choice(text, settings),
+            _ => return self.check_romanji(text, submitted, settings),
+        }
+        text.len()
+    }
+
+    /// Grade the text against the accepted spellings of each syllabogram in turn.
+    /// A syllabogram remains unanswered while the text could still be extended into one
+    ///   of its spellings, unless the text has been submitted.
+    fn check_romanji(&mut self, text: &str, submitted: bool, settings: &GameSettings) -> usize {
+        if settings.confirm_answers && !submitted {
+            return 0
         }
-        if "あいうえおんアイウエオ".contains(&self.translation.kana)
-            || text.len() == self.translation.romanji.len()
-        {
-            let mut is_correct = self.translation.romanji == text;
-            if let Some(alt_romanji) = self.translation.alt_romanji {
-                is_correct |= alt_romanji == text;
+        let mut results: Vec<(String, Grade)> = Vec::with_capacity(self.translations.len());
+        let mut remaining = text;
+        for translation in &self.translations {
+            let accepted = translation.accepted_spellings(settings.romanization);
+            // The spellings of every system are matched, as those of the systems which
+            //   are not accepted are near-misses.
+            let trie = SpellingTrie::new(translation.accepted_spellings(RomanizationSystems::default()));
+            let spelling_grade = |length: usize| match accepted.contains(&&remaining[..length]) {
+                true => Grade::Correct,
+                false => Grade::NearMiss,
+            };
+            let (length, grade) = match trie.matches(remaining) {
+                Match::Complete(length) => (length, spelling_grade(length)),
+                Match::Pending(Some(length)) if submitted => (length, spelling_grade(length)),
+                Match::Pending(None) if submitted => (remaining.len(), Grade::Wrong),
+                Match::Pending(_) => return 0,
+                Match::Mismatch(length) => {
+                    let slip = accepted.iter()
+                        .map(|spelling| match_slip(remaining, spelling))
+                        .find(|slip| !matches!(slip, Match::Mismatch(_)));
+                    match slip {
+                        // A slip which spells another kana is a genuine confusion of the two.
+                        Some(Match::Complete(length))
+                            if mistaken_for(translation.kana, &remaining[..length]).is_none() => {
+                            (length, Grade::NearMiss)
+                        },
+                        Some(Match::Pending(_)) if !submitted => return 0,
+                        _ => (length, Grade::Wrong),
+                    }
+                },
+            };
+            results.push((remaining[..length].to_string(), grade));
+            remaining = &remaining[length..];
+        }
+        // A confirmed answer is made up of the whole text, so anything beyond the spellings is wrong.
+        if settings.confirm_answers && !remaining.is_empty() {
+            if let Some((text, grade)) = results.last_mut() {
+                text.push_str(remaining);
+                *grade = Grade::Wrong;
             }
-            self.state = ContentState::Answered(is_correct);
+            remaining = "";
+        }
+        self.state = ContentState::Answered(results);
+        text.len() - remaining.len()
+    }
+
+    /// Options are compared whole, as one may be the beginning of another, i.e. "n" and "na".
+    fn check_choice(&mut self, text: &str, settings: &GameSettings) {
+        let is_correct = self.translations.iter()
+            .all(|translation| translation.accepted_spellings(settings.romanization).contains(&text));
+        self.state = ContentState::Answered(vec![(text.to_string(), Grade::from(is_correct))]);
+    }
+
+    fn check_kana(&mut self, text: &str, submitted: bool, settings: &GameSettings) {
+        let kana = self.kana();
+        // Text containing ASCII is still being entered through an IME.
+        let is_complete = !text.chars().any(|c| c.is_ascii())
+            && text.chars().count() >= kana.chars().count();
+        if submitted || is_complete {
+            // The kana of the other syllabary is a near-miss unless either syllabary is accepted.
+            let grade = if text == kana {
+                Grade::Correct
+            } else if to_hiragana(text) == to_hiragana(&kana) {
+                match settings.accept_either_syllabary {
+                    true => Grade::Correct,
+                    false => Grade::NearMiss,
+                }
+            } else {
+                Grade::Wrong
+            };
+            self.state = ContentState::Answered(vec![(text.to_string(), grade)]);
         }
     }
 
     fn render(&self) -> Html {
         let style = format!("width: {}em", self.size);
+        let is_answered = matches!(self.state, ContentState::Answered(_));
+        let (shown, hidden) = match self.mode {
+            GameMode::Production => (self.romanji(), self.kana()),
+            // The kana of the Listening mode are only revealed once answered.
+            GameMode::Listening if !is_answered => ("♪".repeat(self.translations.len()), self.romanji()),
+            _ => (self.kana(), self.romanji()),
+        };
         let mut class = "white";
         let mut small_above = VNode::default();
+        let mut content = html! { { shown } };
 
-        if let ContentState::Answered(is_correct) = self.state {
-            if is_correct {
-                class = "green";
-            } else {
-                class = "red";
+        if let ContentState::Answered(results) = &self.state {
+            // The content takes the color of its worst graded syllabogram.
+            let grade = results.iter()
+                .map(|&(_, grade)| grade)
+                .min_by(|a, b| a.weight().total_cmp(&b.weight()))
+                .unwrap_or(Grade::Correct);
+            class = grade.class();
+            if !grade.is_correct() {
                 small_above = html!{
-                    <span class="small-above">
-                        { <&'static str>::clone(&self.translation.romanji) }
-                    </span>
+                    <span class="small-above">{ hidden }</span>
                 };
+                // Mark how each syllabogram of a word was answered.
+                if self.translations.len() > 1 {
+                    content = html! {
+                        for self.translations.iter().zip(results).map(|(t, (_, grade))| {
+                            html! { <span class=grade.class()>{ t.symbol }</span> }
+                        })
+                    };
+                }
             }
         }
 
         return html! {
-            <span class=class style=style>
+            <span class=class style=style title=self.meaning>
                 { small_above }
-                { <&'static str>::clone(&self.translation.kana) }
+                { content }
             </span>
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use strum::IntoEnumIterator;
+
+    use crate::translations::get_translation;
+
+    use super::*;
+
+    const LENGTH: usize = 30;
+    const SEED: u64 = 7;
+
+    fn generator(strategy: SchedulerStrategy, seed: u64) -> ContentGenerator {
+        let mut generator = ContentGenerator::new(strategy, seed);
+        let translations = Kana::iter()
+            .filter(|kana| kana.chart().is_some())
+            .take(20)
+            .map(Kana::translation)
+            .collect();
+        generator.set_translations(Rc::new(translations));
+        generator
+    }
+
+    fn kana(contents: &[Content]) -> Vec<String> {
+        contents.iter().map(Content::kana).collect()
+    }
+
+    #[test]
+    fn contents_are_reproducible_from_the_seed() {
+        for mode in [GameMode::Recognition, GameMode::Choice, GameMode::Words] {
+            let settings = GameSettings { mode, ..GameSettings::default() };
+            for strategy in SchedulerStrategy::iter() {
+                let contents = kana(&generator(strategy, SEED).fill(LENGTH, &settings));
+                assert_eq!(contents, kana(&generator(strategy, SEED).fill(LENGTH, &settings)));
+                assert_ne!(contents, kana(&generator(strategy, SEED + 1).fill(LENGTH, &settings)));
+            }
+        }
+    }
+
+    #[test]
+    fn choices_are_reproducible_from_the_seed() {
+        let settings = GameSettings { mode: GameMode::Choice, ..GameSettings::default() };
+        let choices = |seed| -> Vec<Vec<&str>> {
+            generator(SchedulerStrategy::Uniform, seed).fill(LENGTH, &settings)
+                .into_iter()
+                .map(|content| content.choices)
+                .collect()
+        };
+        assert_eq!(choices(SEED), choices(SEED));
+    }
+
+    #[test]
+    fn resample_continues_the_sequence_of_the_seed() {
+        let settings = GameSettings::default();
+        let mut resampled = generator(SchedulerStrategy::SpacedRepetition, SEED);
+        let mut contents = resampled.fill(LENGTH, &settings);
+        let index = resampled.resample(&mut contents, LENGTH / 2, &settings);
+        assert_eq!(index, LENGTH / 2 - LENGTH / 3);
+
+        let expected = generator(SchedulerStrategy::SpacedRepetition, SEED).fill(LENGTH + LENGTH / 3, &settings);
+        assert_eq!(kana(&contents), kana(&expected[LENGTH / 3..]));
+    }
+
+    #[test]
+    fn resample_waits_for_the_middle_of_the_contents() {
+        let settings = GameSettings::default();
+        let mut generator = generator(SchedulerStrategy::Uniform, SEED);
+        let mut contents = generator.fill(LENGTH, &settings);
+        let before = kana(&contents);
+        assert_eq!(generator.resample(&mut contents, LENGTH / 2 - 1, &settings), LENGTH / 2 - 1);
+        assert_eq!(kana(&contents), before);
+    }
+
+    fn grades(symbol: &str, text: &str) -> Vec<Grade> {
+        let translation = get_translation(symbol).expect("kana could not be translated");
+        let mut content = Content::new(vec![translation], GameMode::Recognition, None);
+        content.check(text, true, &GameSettings::default());
+        match content.state {
+            ContentState::Answered(results) => results.into_iter().map(|(_, grade)| grade).collect(),
+            ContentState::Unanswered => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn slips_onto_a_neighbouring_key_are_near_misses() {
+        assert_eq!(grades("か", "ka"), [Grade::Correct]);
+        assert_eq!(grades("か", "ks"), [Grade::NearMiss]);
+        assert_eq!(grades("し", "sji"), [Grade::NearMiss]);
+    }
+
+    #[test]
+    fn slips_which_spell_another_kana_are_wrong() {
+        assert_eq!(grades("き", "ko"), [Grade::Wrong]);
+        assert_eq!(grades("か", "kp"), [Grade::Wrong]);
+    }
+
+    #[test]
+    fn kana_joined_with_a_sokuon_or_choonpu_are_graded() {
+        assert_eq!(grades("っき", "kki"), [Grade::Correct]);
+        assert_eq!(grades("っち", "tchi"), [Grade::Correct]);
+        assert_eq!(grades("コー", "kou"), [Grade::Correct]);
+        assert_eq!(grades("せー", "sei"), [Grade::Correct]);
+        assert_eq!(grades("セー", "sē"), [Grade::Correct]);
+        assert_eq!(grades("セー", "sei"), [Grade::Wrong]);
+        assert_eq!(grades("ッキー", "kkī"), [Grade::Correct]);
+    }
+}
@@ -0,0 +1,84 @@
+use strum::IntoEnumIterator;
+use yew::prelude::*;
+use yewtil::NeqAssign;
+
+use crate::{
+    charts::{chart_layout, ChartVersion},
+    kana::{Kana, SyllabaryVersion},
+};
+use super::{GameMessage, GameScreen};
+
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct KanaKeyboardProperties {
+    pub hidden: bool,
+    pub syllabaries: Vec<SyllabaryVersion>,
+}
+
+/// An on-screen keyboard laid out like the kana charts, for answering in Production mode.
+pub struct KanaKeyboard {
+    props: KanaKeyboardProperties,
+    onkey: Callback<String>,
+}
+
+impl Component for KanaKeyboard {
+    type Message = ();
+    type Properties = KanaKeyboardProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let onkey = {
+            let parent = link
+                .get_parent().expect("KanaKeyboard component does not have parent")
+                .clone().downcast::<GameScreen>();
+            parent.callback(GameMessage::KeyboardInput)
+        };
+        Self { props, onkey }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props.neq_assign(props)
+    }
+
+    fn view(&self) -> Html {
+        if self.props.hidden {
+            return html! {}
+        }
+        return html! {
+            <div class="kana-keyboard">
+                { for self.props.syllabaries.iter().map(|&syllabary| html! {
+                    <div class="kana-chart-collection">
+                        { for ChartVersion::iter().map(|chart| self.render_chart(chart, syllabary)) }
+                    </div>
+                }) }
+            </div>
+        }
+    }
+}
+
+impl KanaKeyboard {
+    fn render_chart(&self, chart: ChartVersion, syllabary: SyllabaryVersion) -> Html {
+        return html! {
+            <div class="kana-chart">
+                { for chart_layout(chart, syllabary).iter().map(|row| html! {
+                    <div class="kana-row">
+                        { for row.iter().map(|&kana| self.render_key(kana)) }
+                    </div>
+                }) }
+            </div>
+        }
+    }
+
+    fn render_key(&self, kana: Option<Kana>) -> Html {
+        let kana = match kana {
+            Some(kana) => kana.symbol(),
+            // As with the KanaButton, void keys are given content to keep their size consistent.
+            None => return html! { <button void="true" disabled=true>{ "ん" }</button> },
+        };
+        let onclick = self.onkey.reform(move |_: MouseEvent| kana.to_string());
+        return html! { <button onclick=onclick>{ kana }</button> }
+    }
+}
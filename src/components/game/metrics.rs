@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use yew::{Html, html};
+
+use crate::{components::color::{GREEN, ORANGE, RED, YELLOW}, grading::Grade};
+use super::Answer;
+
+/// The number of most recent answers over which the accuracy and response times are shown.
+const WINDOW: usize = 20;
+/// Correct answers are slow if they take at least this many milliseconds...
+const SLOW_MINIMUM: f64 = 1500.0;
+/// ... and this many times the median response time of the recent correct answers.
+const SLOW_FACTOR: f64 = 2.0;
+
+
+/// The timing of a single answer.
+struct Timing {
+    response_time: f64,
+    grade: Grade,
+    is_slow: bool,
+}
+
+/// The live measurements of the current round, shown within the metrics-container.
+#[derive(Default)]
+pub struct Metrics {
+    /// The number of kana answered within the round.
+    answered: usize,
+    /// The number of milliseconds between the start of the round and the latest answer.
+    elapsed: f64,
+    recent: VecDeque<Timing>,
+    /// The number of answers which were correct but slow.
+    slow: usize,
+}
+
+impl Metrics {
+
+    /// The number of kana answered per minute.
+    pub fn kpm(&self) -> f64 {
+        match self.elapsed {
+            elapsed if elapsed > 0.0 => self.answered as f64 / (elapsed / 60_000.0),
+            _ => 0.0,
+        }
+    }
+
+    /// The accuracy of the recent answers, weighted by their grades.
+    pub fn rolling_accuracy(&self) -> Option<f32> {
+        match self.recent.len() {
+            0 => None,
+            length => {
+                let points: f32 = self.recent.iter().map(|t| t.grade.weight()).sum();
+                Some(points / length as f32)
+            },
+        }
+    }
+
+    pub fn slow(&self) -> usize {
+        self.slow
+    }
+
+    /// Record the answers given `elapsed` milliseconds after the start of the round.
+    pub fn record(&mut self, answers: &[Answer], elapsed: f64) {
+        for answer in answers {
+            let is_slow = answer.grade.is_correct() && self.is_slow(answer.response_time);
+            if is_slow {
+                self.slow += 1;
+            }
+            self.recent.push_back(Timing {
+                response_time: answer.response_time,
+                grade: answer.grade,
+                is_slow,
+            });
+            if self.recent.len() > WINDOW {
+                self.recent.pop_front();
+            }
+        }
+        self.answered += answers.len();
+        self.elapsed = elapsed;
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Whether the response time is slow compared to the recent correct answers.
+    fn is_slow(&self, response_time: f64) -> bool {
+        let mut times: Vec<f64> = self.recent.iter()
+            .filter(|t| t.grade.is_correct())
+            .map(|t| t.response_time)
+            .collect();
+        if times.is_empty() {
+            return false
+        }
+        times.sort_by(|a, b| a.total_cmp(b));
+        let median = times[times.len() / 2];
+        response_time >= SLOW_MINIMUM && response_time >= median * SLOW_FACTOR
+    }
+
+    pub fn render(&self) -> Html {
+        let accuracy = match self.rolling_accuracy() {
+            Some(accuracy) => format!("{:.0}%", accuracy * 100.0),
+            None => String::from("--%"),
+        };
+        return html! {
+            <>
+            <div class="kpm" title="Kana per minute">
+                <div class="metric-value">{ format!("{:.0}", self.kpm()) }</div>
+                <div class="metric-label">{ "KPM" }</div>
+            </div>
+            <div class="rolling-accuracy" title=format!("Accuracy of the last {} answers", WINDOW)>
+                { accuracy }
+            </div>
+            <div class="sparkline" title="Response times of the recent answers">
+                { self.render_sparkline() }
+            </div>
+            </>
+        }
+    }
+
+    /// Render a bar for each of the recent response times, scaled to the slowest of them.
+    fn render_sparkline(&self) -> Html {
+        let slowest = self.recent.iter().map(|t| t.response_time).fold(1.0, f64::max);
+        let view_box = format!("0 0 {} 100", WINDOW);
+        return html! {
+            <svg preserveAspectRatio="none" viewBox=view_box>
+                { for self.recent.iter().enumerate().map(|(index, timing)| {
+                    let height = (timing.response_time / slowest * 100.0).max(5.0);
+                    let color = match timing {
+                        Timing { grade: Grade::Wrong, .. } => RED,
+                        Timing { grade: Grade::NearMiss, .. } => ORANGE,
+                        Timing { is_slow: true, .. } => YELLOW,
+                        _ => GREEN,
+                    };
+                    html! {
+                        <rect
+                            fill=color.as_string()
+                            height=height.to_string()
+                            width="0.8"
+                            x=index.to_string()
+                            y=(100.0 - height).to_string()
+                        />
+                    }
+                }) }
+            </svg>
+        }
+    }
+}
@@ -1,15 +1,25 @@
 pub mod screen;
 mod clock;
 mod kana;
+mod keyboard;
+mod metrics;
 mod romanji;
 mod score;
+mod settings;
+mod summary;
+mod writing;
 
 use clock::Clock;
-use kana::KanaLine;
+use kana::{Answer, KanaLine};
+use keyboard::KanaKeyboard;
+use metrics::Metrics;
 use romanji::RomanjiLine;
 use score::Score;
 use screen::GameMessage;
+use summary::RoundSummary;
+use writing::WritingPad;
 
 pub use screen::GameScreen;
+pub use settings::{GameMode, GameSettings, SessionType, MAX_FIRST_TO};
 
 pub type KanaTranslationList = std::rc::Rc<Vec<&'static crate::KanaTranslation>>;
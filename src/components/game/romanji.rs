@@ -1,10 +1,16 @@
 use yew::prelude::*;
 use yewtil::NeqAssign;
 
+use crate::utils::some_if;
 use super::{GameMessage, GameScreen};
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct RomanjiLineProperties {
+    /// Whether answers are only checked once submitted, in which case Space submits them.
+    #[prop_or_default]
+    pub confirm_answers: bool,
+    #[prop_or_default]
+    pub disabled: bool,
     pub hidden: bool,
     pub input_ref: NodeRef
 }
@@ -12,23 +18,46 @@ pub struct RomanjiLineProperties {
 pub struct RomanjiLine {
     props: RomanjiLineProperties,
     oninput: Callback<InputData>,
+    onkeydown: Callback<KeyboardEvent>,
+    onsubmit: Callback<()>,
 }
 
 impl Component for RomanjiLine {
-    type Message = ();
+    type Message = KeyboardEvent;
     type Properties = RomanjiLineProperties;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        let oninput = {
-            let parent = link
-                .get_parent().expect("RomanjiLine component does not have parent")
-                .clone().downcast::<GameScreen>();
-            parent.callback(|e: InputData| GameMessage::TextInput(e.value))
-        };
-        Self { props, oninput }
+        let parent = link
+            .get_parent().expect("RomanjiLine component does not have parent")
+            .clone().downcast::<GameScreen>();
+        // Input which is still being composed by an IME is not yet an answer.
+        let oninput = parent.batch_callback(|e: InputData| {
+            some_if!(!e.event.is_composing(), GameMessage::TextInput(e.value))
+        });
+        let onkeydown = link.callback(|e: KeyboardEvent| e);
+        let onsubmit = parent.callback(|_| GameMessage::Submit);
+        Self { props, oninput, onkeydown, onsubmit }
     }
 
-    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+    fn update(&mut self, event: Self::Message) -> ShouldRender {
+        if event.is_composing() {
+            return false
+        }
+        // Spaces are never part of an answer, so they are either dropped or,
+        //   when answers are confirmed, submit the answer just as Enter does.
+        match event.key().as_str() {
+            "Enter" => {
+                event.prevent_default();
+                self.onsubmit.emit(());
+            },
+            " " => {
+                event.prevent_default();
+                if self.props.confirm_answers {
+                    self.onsubmit.emit(());
+                }
+            },
+            _ => {},
+        }
         false
     }
 
@@ -43,10 +72,12 @@ impl Component for RomanjiLine {
                     autocapitalize="off"
                     autocomplete="off"
                     autocorrect="off"
+                    disabled=self.props.disabled
                     spellcheck="false"
                     ref=self.props.input_ref.clone()
                     type="visiblePassword"
                     oninput=self.oninput.clone()
+                    onkeydown=self.onkeydown.clone()
                 />
             </div>
         }
@@ -1,11 +1,12 @@
 use yew::{Html, html};
 
-const RED: Color = Color { h: 6, s: 78, l: 57 };
-const GREEN: Color = Color { h: 145, s: 70, l: 50 };
+use crate::{components::color::{GREEN, RED}, grading::Grade};
 
 #[derive(Default)]
 pub struct Score {
     correct: usize,
+    /// The sum of the weights of the grades, where near-misses earn partial credit.
+    points: f32,
     total: usize,
 }
 
@@ -15,7 +16,7 @@ impl Score {
         if self.total == 0 {
             return "".into()
         }
-        let percent = (self.correct as f32) / (self.total as f32);
+        let percent = self.points / (self.total as f32);
         RED.interpolate_to(&GREEN, percent.powi(4)).as_string()
     }
 
@@ -24,48 +25,41 @@ impl Score {
         return html!{
             <div class="score" style=style>
                 { crate::components::icons::square_check_solid() }
-                <div class="score-number"> {self.correct} </div>
+                <div class="score-number"> {self.points_string()} </div>
                 <div> {"/"} </div>
                 <div class="score-number"> {self.total} </div>
             </div>
         }
     }
 
-    pub fn reset(&mut self) {
-        self.correct = 0;
-        self.total = 0;
+    pub fn correct(&self) -> usize {
+        self.correct
     }
 
-    pub fn tally(&mut self, is_correct: bool) {
-        self.total += 1;
-        if is_correct {
-            self.correct += 1;
+    /// The points as shown, which only have a decimal place when a near-miss has been made.
+    pub fn points_string(&self) -> String {
+        if self.points.fract() == 0.0 {
+            format!("{:.0}", self.points)
+        } else {
+            format!("{:.1}", self.points)
         }
     }
-}
 
-struct Color {
-    h: u8,
-    s: u8,
-    l: u8,
-}
+    pub fn total(&self) -> usize {
+        self.total
+    }
 
-impl Color {
-    fn as_string(&self) -> String {
-        format!("hsl({},{}%,{}%)", self.h, self.s, self.l)
+    pub fn reset(&mut self) {
+        self.correct = 0;
+        self.points = 0.0;
+        self.total = 0;
     }
 
-    fn interpolate_to(&self, other: &Self, percent: f32) -> Color {
-        Color {
-            h: interpolate(&self.h, &other.h, &percent),
-            s: interpolate(&self.s, &other.s, &percent),
-            l: interpolate(&self.l, &other.l, &percent),
+    pub fn tally(&mut self, grade: Grade) {
+        self.total += 1;
+        self.points += grade.weight();
+        if grade.is_correct() {
+            self.correct += 1;
         }
     }
 }
-
-fn interpolate(from: &u8, to: &u8, percent: &f32) -> u8 {
-    let from = *from as f32;
-    let to = *to as f32;
-    (from + (percent * (to - from))) as u8
-}
\ No newline at end of file
@@ -1,41 +1,87 @@
+use std::rc::Rc;
+
 use yew::prelude::*;
+use yew::services::ConsoleService;
 use yew::web_sys::HtmlInputElement;
 use yewtil::NeqAssign;
 
+use crate::{
+    kana::{Kana, SyllabaryVersion},
+    statistics::StatisticsHandle,
+    translations::{get_translation, get_translations},
+    utils::{now, some_if},
+};
 use super::super::{
-    app::{App, Screen},
-    hide_state::HideState
+    app::{App, AppMessage, Screen},
+    hide_state::HideState,
 };
 use super::{
+    Answer,
     Clock,
+    GameMode,
+    GameSettings,
+    KanaKeyboard,
     KanaLine,
     KanaTranslationList,
+    Metrics,
     RomanjiLine,
+    RoundSummary,
     Score,
+    SessionType,
+    WritingPad,
 };
 
 
 pub enum GameMessage {
     TextInput(String),
-    KanaShift(bool),
+    KeyboardInput(String),
+    Submit,
+    KanaShift(Vec<Answer>),
+    /// The text typed beyond an answer, which begins the next answer.
+    CarryOver(String),
+    TimeUp,
+    Restart,
 }
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct GameProperties {
     pub hide_state: HideState,
     #[prop_or_default]
-    pub translations: KanaTranslationList,
+    pub kana: Rc<Vec<Kana>>,
+    #[prop_or_default]
+    pub paused: bool,
     pub screen_type: Screen,
+    /// The seed of the first round, from which the kana of every round are generated.
+    #[prop_or_default]
+    pub seed: u64,
+    /// Whether the seed was given by the link, in which case every player is drilled identically.
+    #[prop_or_default]
+    pub shared_seed: bool,
+    pub settings: GameSettings,
+    pub statistics: StatisticsHandle,
 }
 
 pub struct GameScreen {
     props: GameProperties,
+    link: ComponentLink<Self>,
     active: bool,
+    /// The correctness of the latest answers, as read out by screen readers.
+    announcement: String,
     input_ref: NodeRef,
+    metrics: Metrics,
     score: Score,
+    /// When the first input of the round was made.
+    started: f64,
+    submitted: bool,
+    /// The results of the round, once it has ended.
+    summary: Option<RoundSummary>,
     text: String,
     onanimationstart: Callback<AnimationEvent>,
     onanimationend: Callback<AnimationEvent>,
+    onleave: Callback<MouseEvent>,
+    onresume: Callback<()>,
+    /// The number of times the round has been restarted, which regenerates the kana in play.
+    round: usize,
 }
 
 impl Component for GameScreen {
@@ -46,33 +92,114 @@ impl Component for GameScreen {
         let app = link.get_parent().unwrap().clone().downcast::<App>();
         let onanimationstart = props.screen_type.onanimationstart(&app);
         let onanimationend = props.screen_type.onanimationend(&app);
+        let onleave = app.callback(|_| AppMessage::LeaveGame);
+        let onresume = app.callback(|_| AppMessage::SetPaused(false));
         Self {
             props,
+            link,
             active: false,
+            announcement: String::default(),
             input_ref: NodeRef::default(),
+            metrics: Metrics::default(),
             score: Score::default(),
+            started: 0.0,
+            submitted: false,
+            summary: None,
             text: String::default(),
             onanimationstart,
             onanimationend,
+            onleave,
+            onresume,
+            round: 0,
         }
     }
 
     fn update(&mut self, message: Self::Message) -> ShouldRender {
         match message {
             Self::Message::TextInput(text) => {
-                self.active = true;
+                if self.summary.is_some() || self.props.paused {
+                    return false
+                }
+                self.activate();
                 self.text = text;
                 true
             },
-            Self::Message::KanaShift(is_correct) => {
-                self.score.tally(is_correct);
-                self.text = String::default();
+            Self::Message::KeyboardInput(kana) => {
+                if self.summary.is_some() || self.props.paused {
+                    return false
+                }
+                self.update(Self::Message::TextInput(kana));
+                self.submitted = true;
+                true
+            },
+            Self::Message::Submit => {
+                if self.props.paused {
+                    return false
+                }
+                // The input is read directly as the final text of an IME composition
+                //   does not necessarily produce a (non-composing) input event.
+                if let Some(input) = self.html_input_element() {
+                    self.update(Self::Message::TextInput(input.value()));
+                }
+                // Submitting nothing would otherwise leave the next keystroke graded as a whole answer.
+                self.submitted = !self.text.is_empty();
+                true
+            },
+            Self::Message::KanaShift(answers) => {
+                // Written answers are not preceded by any text input.
+                self.activate();
+                let is_mistaken = answers.iter().any(|answer| answer.grade.is_wrong());
+                self.announcement = answers.iter()
+                    .map(Answer::announcement)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                self.metrics.record(&answers, now() - self.started);
+                {
+                    let mut statistics = self.props.statistics.borrow_mut();
+                    for answer in answers {
+                        self.score.tally(answer.grade);
+                        statistics.record(
+                            answer.translation,
+                            &answer.text,
+                            answer.grade,
+                            answer.response_time,
+                        );
+                    }
+                    statistics.save();
+                }
+                self.clear_input();
+
+                let session = self.props.settings.session;
+                let is_over = match session {
+                    SessionType::SuddenDeath => is_mistaken,
+                    _ => self.props.settings.target().is_some_and(|target| self.score.correct() >= target),
+                };
+                if is_over {
+                    self.finish();
+                }
+                true
+            },
+            Self::Message::CarryOver(text) => {
+                if self.summary.is_some() {
+                    return false
+                }
+                self.text = text;
                 if let Some(input) = self.html_input_element() {
                     input.set_value(&self.text);
-                    input.focus().ok();
                 }
                 true
-            }
+            },
+            Self::Message::TimeUp => {
+                self.finish();
+                true
+            },
+            Self::Message::Restart => {
+                self.reset();
+                self.clear_input();
+                self.round += 1;
+                self.onresume.emit(());
+                true
+            },
         }
     }
 
@@ -83,35 +210,92 @@ impl Component for GameScreen {
                 input.focus().ok();
             }
         } else {
-            self.active = false;
-            self.score.reset();
+            self.reset();
+            self.round = 0;
         }
         should_render
     }
 
     fn view(&self) -> Html {
+        let keyboard_hidden = self.props.settings.mode != GameMode::Production;
+        let symbols: Vec<&str> = self.props.kana.iter().map(|kana| kana.symbol()).collect();
+        // Any kana which cannot be translated are left out, rather than halting the game.
+        let translations: KanaTranslationList = Rc::new(get_translations(&symbols).unwrap_or_else(|error| {
+            ConsoleService::error(&format!("Could not translate the selected kana: {}", error));
+            symbols.iter().filter_map(|symbol| get_translation(symbol)).collect()
+        }));
+        // The KanaLine is hidden while the round is over, so that it is refilled upon restarting.
+        let is_over = self.summary.is_some();
+        // Answers cannot be given while the game is paused, but the round carries on afterwards.
+        let is_halted = is_over || self.props.paused;
+        let summary = match &self.summary {
+            Some(summary) => summary.render(self.link.callback(|_| GameMessage::Restart)),
+            None => html! {},
+        };
+        // Multiple choice answers are picked from the KanaLine rather than typed.
+        let romanji_line = match self.props.settings.mode {
+            GameMode::Choice => html! {},
+            _ => html! {
+                <RomanjiLine
+                    confirm_answers=self.props.settings.confirm_answers
+                    disabled=is_halted
+                    hidden=self.props.hide_state.is_hidden()
+                    input_ref=self.input_ref.clone()
+                />
+            },
+        };
+        let drill = match self.props.settings.mode {
+            GameMode::Writing => html! {
+                <WritingPad
+                    disabled=is_halted
+                    kana=self.props.kana.clone()
+                    seed=self.seed()
+                    shared_seed=self.props.shared_seed
+                />
+            },
+            _ => html! {
+                <>
+                    <KanaLine
+                        hidden=self.props.hide_state.is_hidden() || is_over
+                        length=100
+                        seed=self.seed()
+                        shared_seed=self.props.shared_seed
+                        settings=self.props.settings.clone()
+                        statistics=self.props.statistics.clone()
+                        submitted=self.submitted
+                        text=self.text.clone()
+                        translations=translations
+                    />
+                    { romanji_line }
+                </>
+            },
+        };
         return html! {
             <div
                 class="game-screen-container"
+                paused=some_if!(self.props.paused, "true")
                 state=self.props.hide_state.as_string()
                 style=self.props.hide_state.display_none()
                 onanimationend=self.onanimationend.clone()
                 onanimationstart=self.onanimationstart.clone()
             >
-                <KanaLine
-                    hidden=self.props.hide_state.is_hidden()
-                    length=100
-                    text=self.text.clone()
-                    translations=self.props.translations.clone()
-                />
-                <RomanjiLine
-                    hidden=self.props.hide_state.is_hidden()
-                    input_ref=self.input_ref.clone()
-                />
+                { drill }
+                <div aria-live="polite" class="visually-hidden" role="status">{ &self.announcement }</div>
                 <div class="metrics-container">
-                    <Clock active=self.active/>
+                    <Clock
+                        active=self.active
+                        countdown=self.props.settings.session.time_limit()
+                        paused=is_halted
+                    />
                     { self.score.render() }
+                    { self.metrics.render() }
                 </div>
+                { summary }
+                { self.render_pause() }
+                <KanaKeyboard
+                    hidden=keyboard_hidden
+                    syllabaries=self.syllabaries()
+                />
             </div>
         }
     }
@@ -122,4 +306,78 @@ impl GameScreen {
     fn html_input_element(&self) -> Option<HtmlInputElement> {
         self.input_ref.cast::<HtmlInputElement>()
     }
+
+    /// Start the clock and the statistics session upon the first input of the round.
+    fn activate(&mut self) {
+        if !self.active {
+            self.props.statistics.borrow_mut().start_session();
+            self.started = now();
+        }
+        self.active = true;
+    }
+
+    fn clear_input(&mut self) {
+        self.submitted = false;
+        self.text = String::default();
+        if let Some(input) = self.html_input_element() {
+            input.set_value(&self.text);
+            input.focus().ok();
+        }
+    }
+
+    /// End the round, which is then summarized until the player restarts.
+    fn finish(&mut self) {
+        if self.summary.is_some() {
+            return
+        }
+        self.summary = Some(RoundSummary {
+            session: self.props.settings.session,
+            correct: self.score.correct(),
+            total: self.score.total(),
+            slow: self.metrics.slow(),
+            kpm: self.metrics.kpm(),
+            duration: now() - self.started,
+            seed: self.seed(),
+        });
+    }
+
+    /// The seed of the current round, which differs from round to round
+    ///   so that restarting does not replay the same kana.
+    fn seed(&self) -> u64 {
+        self.props.seed.wrapping_add(self.round as u64)
+    }
+
+    fn reset(&mut self) {
+        self.active = false;
+        self.announcement = String::default();
+        self.metrics.reset();
+        self.score.reset();
+        self.summary = None;
+    }
+
+    fn render_pause(&self) -> Html {
+        if !self.props.paused {
+            return html! {}
+        }
+        return html! {
+            <div class="overlay">
+                <div class="overlay-panel">
+                    <div class="overlay-title">{ "Paused" }</div>
+                    <button onclick=self.onresume.reform(|_| ())>{ "Resume" }</button>
+                    <button onclick=self.link.callback(|_| GameMessage::Restart)>{ "Restart" }</button>
+                    <button onclick=self.onleave.clone()>{ "Leave" }</button>
+                </div>
+            </div>
+        }
+    }
+
+    /// The syllabaries of the kana currently in play.
+    fn syllabaries(&self) -> Vec<SyllabaryVersion> {
+        let mut syllabaries: Vec<SyllabaryVersion> = self.props.kana.iter()
+            .map(|kana| kana.syllabary())
+            .collect();
+        syllabaries.sort();
+        syllabaries.dedup();
+        syllabaries
+    }
 }
@@ -0,0 +1,128 @@
+//! The configurable options of a game.
+use strum::{Display, EnumIter};
+
+use crate::{romanization::RomanizationSystems, scheduler::SchedulerStrategy};
+
+/// The number of correct answers which ends a First To round, unless configured otherwise.
+const DEFAULT_FIRST_TO: usize = 25;
+/// The most correct answers a First To round may be configured to end at.
+pub const MAX_FIRST_TO: usize = 250;
+
+
+/// Enumeration of the ways in which the player is drilled.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Display, EnumIter)]
+pub enum GameMode {
+    /// The kana is shown and the player answers with its romanji.
+    #[default]
+    #[strum(serialize = "Kana → Romanji")]
+    Recognition,
+    /// The kana is shown and the player picks its romanji from a handful of options,
+    ///   which include the kana it is most easily confused with.
+    #[strum(serialize = "Multiple Choice")]
+    Choice,
+    /// As with Recognition, but pairs of kana which are easily mistaken for one another
+    ///   are interleaved, favouring those which the player has mistaken before.
+    #[strum(serialize = "Confusables")]
+    Confusables,
+    /// The romanji is shown and the player answers with its kana.
+    #[strum(serialize = "Romanji → Kana")]
+    Production,
+    /// Words made up of the selected kana are shown and the player answers
+    ///   with their romanji.
+    #[strum(serialize = "Words")]
+    Words,
+    /// The kana is pronounced, but not shown, and the player answers with its romanji.
+    #[strum(serialize = "Listening")]
+    Listening,
+    /// The stroke order of a kana is shown and the player writes it by hand.
+    #[strum(serialize = "Writing")]
+    Writing,
+}
+
+impl GameMode {
+
+    /// The strategy for choosing which kana are presented next.
+    pub fn strategy(self) -> SchedulerStrategy {
+        match self {
+            Self::Confusables => SchedulerStrategy::Confusables,
+            _ => SchedulerStrategy::default(),
+        }
+    }
+}
+
+/// Enumeration of the ways in which a round of the game ends.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Display, EnumIter)]
+pub enum SessionType {
+    /// The round never ends.
+    #[default]
+    #[strum(serialize = "Endless")]
+    Endless,
+    /// The round ends once the clock has counted down to zero.
+    #[strum(serialize = "60-Second Sprint")]
+    Sprint,
+    /// The round ends once enough kana have been answered correctly.
+    #[strum(serialize = "First to N")]
+    FirstTo,
+    /// The round ends upon the first mistake.
+    #[strum(serialize = "Sudden Death")]
+    SuddenDeath,
+}
+
+impl SessionType {
+
+    /// The number of seconds the round lasts, if it is timed.
+    pub fn time_limit(self) -> Option<usize> {
+        match self {
+            Self::Sprint => Some(60),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameSettings {
+    pub mode: GameMode,
+    pub session: SessionType,
+    /// The number of correct answers which ends a First To round.
+    pub first_to: usize,
+    /// Whether each kana is pronounced as it becomes current.
+    pub audio: bool,
+    /// Whether Production answers may be given in either syllabary,
+    ///   i.e. "か" is accepted for "カ" and vice versa.
+    pub accept_either_syllabary: bool,
+    /// Whether romanji answers are only graded once submitted with Enter or Space,
+    ///   rather than as soon as they can no longer be extended into another spelling.
+    pub confirm_answers: bool,
+    /// The romanization systems whose spellings are accepted as correct.
+    pub romanization: RomanizationSystems,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            session: SessionType::default(),
+            first_to: DEFAULT_FIRST_TO,
+            audio: false,
+            accept_either_syllabary: false,
+            confirm_answers: false,
+            romanization: RomanizationSystems::default(),
+        }
+    }
+}
+
+impl GameSettings {
+
+    /// The number of correct answers which ends the round, if there is one.
+    pub fn target(&self) -> Option<usize> {
+        match self.session {
+            SessionType::FirstTo => Some(self.first_to),
+            _ => None,
+        }
+    }
+
+    /// Whether each kana is pronounced as it becomes current, which the Listening mode requires.
+    pub fn is_pronounced(&self) -> bool {
+        self.audio || self.mode == GameMode::Listening
+    }
+}
@@ -0,0 +1,62 @@
+use yew::{html, Callback, Html, MouseEvent};
+
+use super::SessionType;
+
+
+/// The results of a round which has ended.
+pub struct RoundSummary {
+    pub session: SessionType,
+    pub correct: usize,
+    pub total: usize,
+    /// The number of answers which were correct but slow.
+    pub slow: usize,
+    /// The number of kana answered per minute.
+    pub kpm: f64,
+    /// The number of milliseconds between the first input and the end of the round.
+    pub duration: f64,
+    /// The seed the kana of the round were generated from, which replays them when given by the link.
+    pub seed: u64,
+}
+
+impl RoundSummary {
+
+    pub fn accuracy(&self) -> f32 {
+        self.correct as f32 / self.total.max(1) as f32
+    }
+
+    pub fn render(&self, onrestart: Callback<MouseEvent>) -> Html {
+        let (title, description) = match self.session {
+            SessionType::Sprint => (
+                "Time's Up!",
+                format!("{} correct in {} seconds", self.correct, self.session.time_limit().unwrap_or(0)),
+            ),
+            // Endless rounds never end, and so are never summarized.
+            SessionType::FirstTo | SessionType::Endless => (
+                "Finished!",
+                format!("{} correct in {}", self.correct, format_duration(self.duration)),
+            ),
+            SessionType::SuddenDeath => (
+                "Game Over",
+                format!("{} correct before the first mistake", self.correct),
+            ),
+        };
+        let accuracy = format!("{:.0}% accuracy over {} answers", self.accuracy() * 100.0, self.total);
+        let speed = format!("{:.0} kana per minute, {} correct but slow", self.kpm, self.slow);
+        return html! {
+            <div class="round-summary">
+                <div class="round-summary-title">{ title }</div>
+                <div>{ description }</div>
+                <div>{ accuracy }</div>
+                <div>{ speed }</div>
+                <div>{ format!("Seed {}", self.seed) }</div>
+                <button onclick=onrestart>{ "Play Again" }</button>
+            </div>
+        }
+    }
+}
+
+/// Format a number of milliseconds as minutes and seconds, i.e. "1:05.3".
+fn format_duration(milliseconds: f64) -> String {
+    let seconds = milliseconds / 1000.0;
+    format!("{}:{:04.1}", (seconds / 60.0).floor(), seconds % 60.0)
+}
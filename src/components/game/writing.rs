@@ -0,0 +1,340 @@
+//! The handwriting practice of the Writing mode.
+//!
+//! The stroke order of a kana is animated beside a canvas upon which the player writes it.
+//! Once checked, the handwritten strokes are graded against the reference strokes and
+//! the result is handed to the GameScreen as an answer, just as the KanaLine does.
+use std::rc::Rc;
+
+use rand::{rngs::StdRng, SeedableRng};
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew::web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yewtil::NeqAssign;
+
+use crate::{
+    grading::Grade,
+    kana::Kana,
+    scheduler::{Scheduler, SchedulerStrategy},
+    strokes::{grade, reference_strokes, stroke_paths, Point, Stroke, StrokeGrade, VIEW_BOX},
+    translations::KanaTranslation,
+    utils::now,
+};
+use super::{Answer, GameMessage, GameScreen};
+
+/// The width and height of the canvas in pixels.
+const CANVAS_SIZE: u32 = 327;
+/// The number of seconds each stroke of the stroke order animation takes to draw.
+const STROKE_DURATION: f64 = 0.6;
+
+
+pub enum WritingMessage {
+    StrokeStart(PointerEvent),
+    StrokeMove(PointerEvent),
+    StrokeEnd,
+    Clear,
+    Check,
+    Next,
+    Replay,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct WritingPadProperties {
+    #[prop_or_default]
+    pub disabled: bool,
+    pub kana: Rc<Vec<Kana>>,
+    /// The seed of the round, which starts the kana afresh whenever it changes.
+    #[prop_or_default]
+    pub seed: u64,
+    /// Whether the seed was given by the link, in which case every player is drilled identically.
+    #[prop_or_default]
+    pub shared_seed: bool,
+}
+
+pub struct WritingPad {
+    props: WritingPadProperties,
+    link: ComponentLink<Self>,
+    canvas_ref: NodeRef,
+    current: Option<Kana>,
+    current_since: f64,
+    drawing: bool,
+    grade: Option<StrokeGrade>,
+    onanswer: Callback<Vec<Answer>>,
+    /// The number of times the stroke order animation has been replayed,
+    ///   which keys the animation so that it restarts.
+    replays: usize,
+    rng: StdRng,
+    scheduler: Box<dyn Scheduler>,
+    strokes: Vec<Stroke>,
+    /// The translations of the kana in play which have stroke data.
+    translations: Vec<&'static KanaTranslation>,
+}
+
+impl Component for WritingPad {
+    type Message = WritingMessage;
+    type Properties = WritingPadProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let onanswer = {
+            let parent = link
+                .get_parent().expect("WritingPad component does not have parent")
+                .clone().downcast::<GameScreen>();
+            parent.callback(GameMessage::KanaShift)
+        };
+        let mut pad = Self {
+            props,
+            link,
+            canvas_ref: NodeRef::default(),
+            current: None,
+            current_since: 0.0,
+            drawing: false,
+            grade: None,
+            onanswer,
+            replays: 0,
+            rng: StdRng::seed_from_u64(0),
+            scheduler: SchedulerStrategy::default().build(),
+            strokes: Vec::new(),
+            translations: Vec::new(),
+        };
+        pad.refill();
+        pad
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Self::Message::StrokeStart(event) => {
+                if self.props.disabled || self.grade.is_some() {
+                    return false
+                }
+                if let Some(canvas) = self.canvas() {
+                    canvas.set_pointer_capture(event.pointer_id()).ok();
+                }
+                self.drawing = true;
+                let point = self.to_point(&event);
+                self.strokes.push(vec![point]);
+                self.redraw();
+                false
+            },
+            Self::Message::StrokeMove(event) => {
+                if !self.drawing {
+                    return false
+                }
+                let point = self.to_point(&event);
+                if let Some(stroke) = self.strokes.last_mut() {
+                    stroke.push(point);
+                }
+                self.redraw();
+                false
+            },
+            Self::Message::StrokeEnd => {
+                self.drawing.neq_assign(false)
+            },
+            Self::Message::Clear => {
+                self.strokes.clear();
+                self.redraw();
+                true
+            },
+            Self::Message::Check => {
+                let kana = match self.current {
+                    Some(kana) if !self.strokes.is_empty() && self.grade.is_none() => kana,
+                    _ => return false,
+                };
+                let reference = reference_strokes(kana).unwrap_or_default();
+                let result = grade(&reference, &self.strokes);
+                let translation = kana.translation();
+                let is_correct = result.is_correct();
+                self.scheduler.record(translation, is_correct);
+                // Nothing was typed, so the faults are only shown by the WritingPad.
+                self.onanswer.emit(vec![Answer {
+                    translation,
+                    text: String::new(),
+                    grade: Grade::from(is_correct),
+                    response_time: now() - self.current_since,
+                }]);
+                self.grade = Some(result);
+                true
+            },
+            Self::Message::Next => {
+                self.next();
+                true
+            },
+            Self::Message::Replay => {
+                self.replays += 1;
+                true
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let is_changed = self.props.kana != props.kana
+            || self.props.seed != props.seed
+            || self.props.shared_seed != props.shared_seed;
+        let should_render = self.props.neq_assign(props);
+        if is_changed {
+            self.refill();
+        }
+        should_render
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.redraw();
+    }
+
+    fn view(&self) -> Html {
+        let kana = match self.current {
+            Some(kana) => kana,
+            None => return html! {
+                <div class="writing-pad">
+                    <div class="writing-prompt">
+                        { "None of the selected kana have stroke order data yet." }
+                    </div>
+                </div>
+            },
+        };
+        let onpointerdown = self.link.callback(WritingMessage::StrokeStart);
+        let onpointermove = self.link.callback(WritingMessage::StrokeMove);
+        let onpointerup = self.link.callback(|_| WritingMessage::StrokeEnd);
+        let onpointercancel = self.link.callback(|_| WritingMessage::StrokeEnd);
+        let (result, action) = match &self.grade {
+            Some(grade) => (
+                self.render_grade(grade),
+                html! { <button onclick=self.link.callback(|_| WritingMessage::Next)>{ "Next" }</button> },
+            ),
+            None => (
+                html! {},
+                html! { <button onclick=self.link.callback(|_| WritingMessage::Check)>{ "Check" }</button> },
+            ),
+        };
+        let canvas_size = CANVAS_SIZE.to_string();
+        return html! {
+            <div class="writing-pad">
+                <div class="writing-prompt">
+                    { "Write " }<span class="writing-romanji">{ kana.translation().romanji }</span>
+                </div>
+                <div class="writing-surfaces">
+                    { self.render_stroke_order(kana) }
+                    <canvas
+                        class="writing-canvas"
+                        height=canvas_size.clone()
+                        width=canvas_size
+                        ref=self.canvas_ref.clone()
+                        onpointerdown=onpointerdown
+                        onpointermove=onpointermove
+                        onpointerup=onpointerup
+                        onpointercancel=onpointercancel
+                    />
+                </div>
+                { result }
+                <div class="writing-buttons">
+                    <button onclick=self.link.callback(|_| WritingMessage::Replay)>{ "Replay" }</button>
+                    <button onclick=self.link.callback(|_| WritingMessage::Clear)>{ "Clear" }</button>
+                    { action }
+                </div>
+            </div>
+        }
+    }
+}
+
+impl WritingPad {
+
+    /// Reset the kana in play to those of the properties which have stroke data,
+    ///   scheduled afresh from the seed.
+    fn refill(&mut self) {
+        self.translations = self.props.kana.iter()
+            .filter(|&&kana| stroke_paths(kana).is_some())
+            .map(|kana| kana.translation())
+            .collect();
+        self.rng = StdRng::seed_from_u64(self.props.seed);
+        // Spaced repetition is steered by the answers of the player, which would drill
+        //   players sharing a seed differently.
+        self.scheduler = match self.props.shared_seed {
+            true => SchedulerStrategy::Uniform.build(),
+            false => SchedulerStrategy::default().build(),
+        };
+        self.next();
+    }
+
+    fn next(&mut self) {
+        self.current = self.scheduler.next(&self.translations, &mut self.rng)
+            .map(|translation| translation.kana);
+        self.current_since = now();
+        self.grade = None;
+        self.strokes.clear();
+        self.replays += 1;
+        self.redraw();
+    }
+
+    fn canvas(&self) -> Option<HtmlCanvasElement> {
+        self.canvas_ref.cast::<HtmlCanvasElement>()
+    }
+
+    /// The position of the pointer within the VIEW_BOX of the reference strokes.
+    fn to_point(&self, event: &PointerEvent) -> Point {
+        let width = self.canvas().map_or(CANVAS_SIZE as i32, |canvas| canvas.client_width()).max(1);
+        let scale = VIEW_BOX / width as f64;
+        (event.offset_x() as f64 * scale, event.offset_y() as f64 * scale)
+    }
+
+    /// Draw the handwritten strokes onto the canvas.
+    fn redraw(&self) {
+        let context = self.canvas()
+            .and_then(|canvas| canvas.get_context("2d").ok().flatten())
+            .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok());
+        let context = match context {
+            Some(context) => context,
+            None => return,
+        };
+        let scale = CANVAS_SIZE as f64 / VIEW_BOX;
+        context.clear_rect(0.0, 0.0, CANVAS_SIZE as f64, CANVAS_SIZE as f64);
+        context.set_line_width(3.0 * scale);
+        context.set_line_cap("round");
+        context.set_line_join("round");
+        context.set_stroke_style(&"white".into());
+        for stroke in &self.strokes {
+            context.begin_path();
+            for (index, &(x, y)) in stroke.iter().enumerate() {
+                match index {
+                    0 => context.move_to(x * scale, y * scale),
+                    _ => context.line_to(x * scale, y * scale),
+                }
+            }
+            // A single point is drawn as a dot.
+            if let [(x, y)] = stroke.as_slice() {
+                context.line_to(x * scale, y * scale);
+            }
+            context.stroke();
+        }
+    }
+
+    /// The stroke order animation, in which each stroke is drawn after the last.
+    fn render_stroke_order(&self, kana: Kana) -> Html {
+        let paths = stroke_paths(kana).unwrap_or_default();
+        let view_box = format!("0 0 {} {}", VIEW_BOX, VIEW_BOX);
+        return html! {
+            <svg class="stroke-order" key=self.replays.to_string() viewBox=view_box>
+                { for paths.iter().enumerate().map(|(index, d)| {
+                    let style = format!(
+                        "animation-delay: {:.1}s; animation-duration: {:.1}s;",
+                        index as f64 * STROKE_DURATION,
+                        STROKE_DURATION,
+                    );
+                    html! { <path d=*d pathLength="1" style=style /> }
+                }) }
+            </svg>
+        }
+    }
+
+    fn render_grade(&self, grade: &StrokeGrade) -> Html {
+        let similarity = format!("{:.0}% similar", grade.similarity * 100.0);
+        if grade.is_correct() {
+            return html! {
+                <div class="writing-grade green">{ "Correct" }<small>{ similarity }</small></div>
+            }
+        }
+        return html! {
+            <div class="writing-grade red">
+                { for grade.faults.iter().map(|fault| html! { <div>{ fault.to_string() }</div> }) }
+                <small>{ similarity }</small>
+            </div>
+        }
+    }
+}
@@ -1,22 +1,26 @@
 use yew::prelude::*;
 
-use crate::utils::some_if;
-use super::{
-    enums::{ChartVersion, SyllabaryVersion},
-    screen::{KanaSelector, KanaMessage},
+use crate::{
+    charts::ChartVersion,
+    kana::{Kana, SyllabaryVersion},
+    utils::some_if,
 };
+use super::screen::{KanaSelector, KanaMessage};
 
 
 pub struct KanaButton {
     active: bool,
     void: bool,
-    pub symbol: String,
+    /// The kana of the button, which void buttons do not have.
+    pub kana: Option<Kana>,
     callback: Callback<MouseEvent>,
+    onpressstart: Callback<PointerEvent>,
+    onpressend: Callback<PointerEvent>,
 }
 
 impl KanaButton {
     pub fn new(
-        symbol: &str,
+        kana: Option<Kana>,
         button_index: usize,
         row_index: usize,
         chart: ChartVersion,
@@ -25,8 +29,8 @@ impl KanaButton {
     ) -> Self {
         Self {
             active: false,
-            void: symbol.is_ascii(),
-            symbol: String::from(symbol),
+            void: kana.is_none(),
+            kana,
             callback: link.callback(move |_|
                 KanaMessage::ButtonToggle(
                     button_index,
@@ -35,6 +39,9 @@ impl KanaButton {
                     syllabary,
                 )
             ),
+            // Long pressing a button pronounces its kana.
+            onpressstart: link.batch_callback(move |_| kana.map(KanaMessage::PressStart)),
+            onpressend: link.callback(|_| KanaMessage::PressEnd),
         }
     }
 
@@ -45,13 +52,27 @@ impl KanaButton {
     pub fn render(&self) -> Html {
         // Setting the content for a void button is a hack to ensure that the void
         //   buttons are the same size as the non-void buttons.
-        let content: &str = if self.void { "ん" } else { &self.symbol };
+        let content = self.kana.map_or("ん", Kana::symbol);
+        // Void buttons are hidden from screen readers and skipped over when tabbing.
+        let (aria_hidden, aria_pressed, tabindex) = match self.void {
+            true => (Some("true"), None, Some("-1")),
+            false => (None, Some(self.active.to_string()), None),
+        };
         return html! {
-            <button
-              active=some_if!(self.active, "true")
-              onclick=self.callback.clone()
-              void=some_if!(self.void, "true")
-            >{content}</button>
+            <span class="grid-cell" role="gridcell">
+                <button
+                  active=some_if!(self.active, "true")
+                  aria-hidden=aria_hidden
+                  aria-pressed=aria_pressed
+                  onclick=self.callback.clone()
+                  oncontextmenu=Callback::from(|e: MouseEvent| e.prevent_default())
+                  onpointerdown=self.onpressstart.clone()
+                  onpointerleave=self.onpressend.clone()
+                  onpointerup=self.onpressend.clone()
+                  tabindex=tabindex
+                  void=some_if!(self.void, "true")
+                >{content}</button>
+            </span>
         }
     }
 
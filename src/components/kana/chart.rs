@@ -1,15 +1,20 @@
 use yew::prelude::*;
 
-use crate::utils::some_if;
+use crate::{
+    charts::{chart_layout, ChartVersion},
+    kana::{Kana, SyllabaryVersion},
+    utils::some_if,
+};
 use super::{
     row::KanaRow,
-    enums::{ChartVersion, SyllabaryVersion},
     screen::{KanaSelector, KanaMessage},
 };
 
 
 pub struct KanaChart {
     title: String,
+    /// The name of the chart read out by screen readers, i.e. "Basic Katakana".
+    label: String,
     callback: Callback<MouseEvent>,
     pub rows: Vec<KanaRow>,
 }
@@ -20,119 +25,30 @@ impl KanaChart {
         syllabary: SyllabaryVersion,
         link: &ComponentLink<KanaSelector>
     ) -> Self {
-        let symbols =
-            match syllabary {
-                SyllabaryVersion::Hirigana => {
-                    match version {
-                        ChartVersion::Basic => {
-                            vec![
-                                "あいうえお",
-                                "かきくけこ",
-                                "さしすせそ",
-                                "たちつてと",
-                                "なにぬねの",
-                                "はひふへほ",
-                                "まみむめも",
-                                "や ゆ よ",
-                                "らりるれろ",
-                                "わ   を",
-                                "ん    ",
-                            ]
-                        },
-                        ChartVersion::Dakuon => {
-                            vec![
-                                "がぎぐげご",
-                                "ざじずぜぞ",
-                                "だぢづでど",
-                                "ばびぶべぼ",
-                                "ぱぴぷぺぽ",
-                            ]
-                        },
-                        ChartVersion::Combo => {
-                            vec![
-                                "きゃきゅきょ",
-                                "しゃしゅしょ",
-                                "ちゃちゅちょ",
-                                "にゃにゅにょ",
-                                "ひゃひゅひょ",
-                                "みゃみゅみょ",
-                                "りゃりゅりょ",
-                                "ぎゃぎゅぎょ",
-                                "じゃじゅじょ",
-                                "びゃびゅびょ",
-                                "ぴゃぴゅぴょ",
-                            ]
-                        },
-                    }
-                },
-                SyllabaryVersion::Katakana => {
-                    match version {
-                        ChartVersion::Basic => {
-                            vec![
-                                "アイウエオ",
-                                "カキクケコ",
-                                "サシスセソ",
-                                "タチツテト",
-                                "ナニヌネノ",
-                                "ハヒフヘホ",
-                                "マミムメモ",
-                                "ヤ ユ ヨ",
-                                "ラリルレロ",
-                                "ワ   ヲ",
-                                "ン    ",
-                            ]
-                        },
-                        ChartVersion::Dakuon => {
-                            vec![
-                                "ガギグゲゴ",
-                                "ザジズゼゾ",
-                                "ダヂヅデド",
-                                "バビブベボ",
-                                "パピプペポ",
-                                "ヴ    ",
-                            ]
-                        },
-                        ChartVersion::Combo => {
-                            vec![
-                                "キャキュキョ",
-                                "ニャニュニョ",
-                                "ヒャヒュヒョ",
-                                "ミャミュミョ",
-                                "リャリュリョ",
-                                "ギャギュギョ",
-                                "ビャビュビョ",
-                                "ピャピュピョ",
-                                "ウィウェウォ",
-                                "シャシュシェショ",
-                                "チャチュチェチョ",
-                                "ファフィフェフォ",
-                                "ジャジュジェジョ",
-                                "ヴァヴィヴェヴォ",
-
-                            ]
-                        },
-                    }
-                },
-            };
         Self {
             title: version.to_string(),
+            label: format!("{} {}", version, syllabary),
             callback: link.callback(move |_|
                 KanaMessage::ChartToggle(version, syllabary)
             ),
-            rows: symbols
+            rows: chart_layout(version, syllabary)
                 .iter()
                 .enumerate()
-                .map(|(index, &symbols)| {
-                    KanaRow::new(symbols, index, version, syllabary, link)
+                .map(|(index, &row)| {
+                    KanaRow::new(row, index, version, syllabary, link)
                 })
                 .collect(),
         }
     }
 
-    pub fn get_selected_kana(&self) -> Vec<String> {
+    pub fn get_selected_kana(&self) -> Vec<Kana> {
         self.rows.iter().flat_map(KanaRow::get_selected_kana).collect()
     }
 
+    pub fn set_selected_kana(&mut self, kana: &[Kana]) {
+        self.rows.iter_mut().for_each(|row| row.set_selected_kana(kana))
+    }
+
     pub fn is_active(&self) -> bool {
         self.rows.iter().any(KanaRow::is_active)
     }
@@ -149,15 +65,20 @@ impl KanaChart {
     }
 
     pub fn render(&self) -> Html {
+        let is_active = self.is_active();
         return html! {
             <div class="kana-chart">
                 <button
-                    active=some_if!(self.is_active(), "true")
+                    active=some_if!(is_active, "true")
+                    aria-pressed=is_active.to_string()
                     class="chart-title"
                     onclick=self.callback.clone()
                 >{ &self.title }</button>
-                { for self.rows.iter().map(KanaRow::render) }
+                <div aria-label=self.label.clone() role="grid">
+                    { for self.rows.iter().map(KanaRow::render) }
+                </div>
             </div>
         }
     }
-}
\ No newline at end of file
+}
+
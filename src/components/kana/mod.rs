@@ -1,8 +1,7 @@
 mod button;
 mod screen;
-mod enums;
 mod row;
 mod chart;
 mod syllabary;
 
-pub use screen::KanaSelector;
\ No newline at end of file
+pub use screen::KanaSelector;
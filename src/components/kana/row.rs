@@ -1,9 +1,12 @@
 use yew::prelude::*;
 
-use crate::{SyllabogramSegmentation, utils::some_if};
+use crate::{
+    charts::{ChartRow, ChartVersion},
+    kana::{Kana, SyllabaryVersion},
+    utils::some_if,
+};
 use super::{
     button::KanaButton,
-    enums::{ChartVersion, SyllabaryVersion},
     screen::{KanaSelector, KanaMessage},
 };
 
@@ -15,7 +18,7 @@ pub struct KanaRow {
 
 impl KanaRow {
     pub fn new(
-        symbols: &str,
+        row: ChartRow,
         row_index: usize,
         chart: ChartVersion,
         syllabary: SyllabaryVersion,
@@ -29,11 +32,11 @@ impl KanaRow {
                     syllabary,
                 )
             ),
-            buttons: symbols.syllabograms()
+            buttons: row.iter()
                 .enumerate()
-                .map(|(index, syllabogram)| {
+                .map(|(index, &kana)| {
                     KanaButton::new(
-                        syllabogram,
+                        kana,
                         index,
                         row_index,
                         chart,
@@ -45,13 +48,21 @@ impl KanaRow {
         }
     }
 
-    pub fn get_selected_kana(&self) -> Vec<String> {
+    pub fn get_selected_kana(&self) -> Vec<Kana> {
         self.buttons.iter()
             .filter(|&b| b.is_active())
-            .map(|b| b.symbol.clone())
+            .filter_map(|b| b.kana)
             .collect()
     }
 
+    /// Select exactly those buttons whose kana are given.
+    pub fn set_selected_kana(&mut self, kana: &[Kana]) {
+        for button in self.buttons.iter_mut() {
+            let active = button.kana.is_some_and(|k| kana.contains(&k));
+            button.set_active(active);
+        }
+    }
+
     pub fn is_active(&self) -> bool {
         self.buttons.iter().any(KanaButton::is_active)
     }
@@ -75,13 +86,21 @@ impl KanaRow {
             crate::components::icons::square_solid()
         };
 
+        let label = match self.buttons.iter().find_map(|button| button.kana) {
+            Some(kana) => format!("Row {}", kana.symbol()),
+            None => String::from("Row"),
+        };
         return html! {
-            <div class="kana-row">
-                <button
-                    active=some_if!(is_active, "true")
-                    class="checkbox"
-                    onclick=self.callback.clone()
-                >{ checkbox_icon }</button>
+            <div class="kana-row" role="row">
+                <span class="grid-cell" role="gridcell">
+                    <button
+                        active=some_if!(is_active, "true")
+                        aria-label=label
+                        aria-pressed=is_active.to_string()
+                        class="checkbox"
+                        onclick=self.callback.clone()
+                    >{ checkbox_icon }</button>
+                </span>
                 { for self.buttons.iter().map(KanaButton::render) }
             </div>
         }
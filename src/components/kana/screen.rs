@@ -1,17 +1,29 @@
 use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::Duration;
 
 use strum::IntoEnumIterator;
 use yew::prelude::*;
+use yew::services::timeout::{TimeoutService, TimeoutTask};
 use yewtil::NeqAssign;
 
+use crate::{
+    audio::pronounce,
+    charts::ChartVersion,
+    kana::{Kana, SyllabaryVersion},
+    selections::{Preset, SelectionStore},
+    utils::some_if,
+};
 use super::super::{
     app::{App, AppMessage, Screen},
     hide_state::HideState,
 };
-use super::{
-    enums::{ChartVersion, SyllabaryVersion},
-    syllabary::KanaSyllabary,
-};
+use super::syllabary::KanaSyllabary;
+
+/// The number of milliseconds a KanaButton is held for to pronounce its kana.
+const LONG_PRESS: u64 = 500;
+/// The number of milliseconds after a long press is released within which its click is expected.
+const CLICK_WINDOW: u64 = 100;
 
 
 #[allow(clippy::enum_variant_names)]
@@ -20,29 +32,122 @@ pub enum KanaMessage {
     ChartToggle(ChartVersion, SyllabaryVersion),
     RowToggle(usize, ChartVersion, SyllabaryVersion),
     ButtonToggle(usize, usize, ChartVersion, SyllabaryVersion),
+    PressStart(Kana),
+    PressEnd,
+    LongPress(Kana),
+    LongPressSettled,
+    ApplyPreset(Preset),
+    ApplySet(String),
+    SetName(String),
+    SaveSet,
+    RenameSet,
+    DeleteSet,
 }
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct KanaSelectorProperties {
     pub hide_state: HideState,
-    pub screen_type: Screen
+    pub screen_type: Screen,
+    /// The kana to select upon creation in place of the last selection.
+    #[prop_or_default]
+    pub selection: Option<Rc<Vec<Kana>>>,
 }
 
 pub struct KanaSelector {
     props: KanaSelectorProperties,
+    link: ComponentLink<Self>,
+    /// Whether the current press of a KanaButton has been held long enough to pronounce
+    ///   its kana, in which case the press does not also toggle the button.
+    long_pressed: bool,
+    /// The timeout of the press in progress, or of the click window of a released long press.
+    press: Option<TimeoutTask>,
+    /// The custom set which was last applied or saved, which is renamed or deleted.
+    current_set: Option<String>,
+    /// The name typed for saving or renaming a custom set.
+    set_name: String,
+    selections: SelectionStore,
     onanimationstart: Callback<AnimationEvent>,
     onanimationend: Callback<AnimationEvent>,
-    refresh: Callback<Vec<String>>,
+    refresh: Callback<Vec<Kana>>,
     syllabaries: BTreeMap<SyllabaryVersion, KanaSyllabary>,
 }
 
 impl KanaSelector {
 
     /// Iterates through all of the KanaSelector elements and returns all
-    /// kana symbols that are currently selected.
-    fn get_selected_kana(&self) -> Vec<String> {
+    /// kana that are currently selected.
+    fn get_selected_kana(&self) -> Vec<Kana> {
         self.syllabaries.values().flat_map(KanaSyllabary::get_selected_kana).collect()
     }
+
+    /// Select exactly the given kana.
+    fn set_selected_kana(&mut self, kana: &[Kana]) {
+        for syllabary in self.syllabaries.values_mut() {
+            syllabary.set_selected_kana(kana);
+        }
+    }
+
+    /// The presets and custom sets, along with the controls for managing the custom sets.
+    fn render_sets(&self) -> Html {
+        // The kana are compared regardless of the order in which they are laid out.
+        let mut selected = self.get_selected_kana();
+        selected.sort();
+        let set_name = self.set_name.trim();
+        let oninput = self.link.callback(|event: InputData| KanaMessage::SetName(event.value));
+        let presets = Preset::iter().map(|preset| {
+            let mut kana = preset.kana();
+            kana.sort();
+            let is_active = kana == selected;
+            html! {
+                <button
+                    active=some_if!(is_active, "true")
+                    aria-pressed=is_active.to_string()
+                    onclick=self.link.callback(move |_| KanaMessage::ApplyPreset(preset))
+                >{ preset.to_string() }</button>
+            }
+        });
+        let sets = self.selections.names().map(|name| {
+            let is_active = self.current_set.as_deref() == Some(name);
+            let message = name.to_string();
+            html! {
+                <button
+                    active=some_if!(is_active, "true")
+                    aria-pressed=is_active.to_string()
+                    class="custom-set"
+                    onclick=self.link.callback(move |_| KanaMessage::ApplySet(message.clone()))
+                >{ name }</button>
+            }
+        });
+        return html! {
+            <div class="kana-sets">
+                <div class="kana-set-list">
+                    { for presets }
+                    { for sets }
+                </div>
+                <div class="kana-set-editor">
+                    <input
+                        aria-label="Set name"
+                        oninput=oninput
+                        placeholder="Set name"
+                        type="text"
+                        value=self.set_name.clone()
+                    />
+                    <button
+                        disabled=set_name.is_empty()
+                        onclick=self.link.callback(|_| KanaMessage::SaveSet)
+                    >{ "Save" }</button>
+                    <button
+                        disabled=set_name.is_empty() || self.current_set.is_none()
+                        onclick=self.link.callback(|_| KanaMessage::RenameSet)
+                    >{ "Rename" }</button>
+                    <button
+                        disabled=self.current_set.is_none()
+                        onclick=self.link.callback(|_| KanaMessage::DeleteSet)
+                    >{ "Delete" }</button>
+                </div>
+            </div>
+        }
+    }
 }
 
 impl Component for KanaSelector {
@@ -53,7 +158,7 @@ impl Component for KanaSelector {
         let app = link.get_parent().unwrap().clone().downcast::<App>();
         let onanimationstart = props.screen_type.onanimationstart(&app);
         let onanimationend = props.screen_type.onanimationend(&app);
-        let refresh = app.callback(AppMessage::SetSymbols);
+        let refresh = app.callback(AppMessage::SetKana);
 
         let mut syllabaries: BTreeMap<SyllabaryVersion, KanaSyllabary> = BTreeMap::new();
         for syllabary in SyllabaryVersion::iter() {
@@ -62,12 +167,28 @@ impl Component for KanaSelector {
             );
         }
 
-        { // Set the entire Basic Hirigana chart to active.
-            let hiragana = syllabaries.get_mut(&SyllabaryVersion::Hirigana).unwrap();
-            hiragana.charts.get_mut(&ChartVersion::Basic).unwrap().toggle();
-            refresh.emit(hiragana.get_selected_kana());
-        }
-        Self { props, onanimationstart, onanimationend, refresh, syllabaries }
+        let mut selector = Self {
+            props,
+            link,
+            long_pressed: false,
+            press: None,
+            current_set: None,
+            set_name: String::default(),
+            selections: SelectionStore::load(),
+            onanimationstart,
+            onanimationend,
+            refresh,
+            syllabaries,
+        };
+        // Select the kana of the link the app was opened with, or otherwise restore
+        //   the last selection, falling back to the Basic Hiragana chart.
+        let kana = match &selector.props.selection {
+            Some(selection) => selection.to_vec(),
+            None => selector.selections.last().unwrap_or_else(|| Preset::BasicHiragana.kana()),
+        };
+        selector.set_selected_kana(&kana);
+        selector.refresh.emit(selector.get_selected_kana());
+        selector
     }
 
     fn update(&mut self, message: Self::Message) -> ShouldRender {
@@ -88,14 +209,99 @@ impl Component for KanaSelector {
                     .toggle();
             },
             Self::Message::ButtonToggle(button, row, chart, syllabary) => {
+                if self.long_pressed {
+                    self.long_pressed = false;
+                    return false
+                }
                 self.syllabaries.get_mut(&syllabary).unwrap()
                     .charts.get_mut(&chart).unwrap()
                     .rows[row]
                     .buttons[button]
                     .toggle();
             },
+            Self::Message::PressStart(kana) => {
+                self.long_pressed = false;
+                self.press = Some(TimeoutService::spawn(
+                    Duration::from_millis(LONG_PRESS),
+                    self.link.callback(move |_| KanaMessage::LongPress(kana)),
+                ));
+                return false
+            },
+            Self::Message::PressEnd => {
+                // A long press released away from its button is not followed by a click,
+                //   so the click is only ignored for a short while after the release.
+                self.press = match self.long_pressed {
+                    true => Some(TimeoutService::spawn(
+                        Duration::from_millis(CLICK_WINDOW),
+                        self.link.callback(|_| KanaMessage::LongPressSettled),
+                    )),
+                    false => None,
+                };
+                return false
+            },
+            Self::Message::LongPressSettled => {
+                self.press = None;
+                self.long_pressed = false;
+                return false
+            },
+            Self::Message::LongPress(kana) => {
+                self.press = None;
+                self.long_pressed = true;
+                pronounce(kana.symbol());
+                return false
+            },
+            Self::Message::ApplyPreset(preset) => {
+                self.current_set = None;
+                self.set_selected_kana(&preset.kana());
+            },
+            Self::Message::ApplySet(name) => {
+                let kana = match self.selections.get(&name) {
+                    Some(kana) => kana,
+                    None => return false,
+                };
+                self.set_selected_kana(&kana);
+                self.set_name = name.clone();
+                self.current_set = Some(name);
+            },
+            Self::Message::SetName(name) => {
+                return self.set_name.neq_assign(name)
+            },
+            Self::Message::SaveSet => {
+                let name = self.set_name.trim().to_string();
+                if name.is_empty() {
+                    return false
+                }
+                self.selections.insert(&name, &self.get_selected_kana());
+                self.selections.save();
+                self.current_set = Some(name);
+                return true
+            },
+            Self::Message::RenameSet => {
+                let name = self.set_name.trim().to_string();
+                let current = match &self.current_set {
+                    Some(current) if !name.is_empty() => current,
+                    _ => return false,
+                };
+                if !self.selections.rename(current, &name) {
+                    return false
+                }
+                self.selections.save();
+                self.current_set = Some(name);
+                return true
+            },
+            Self::Message::DeleteSet => {
+                if let Some(current) = self.current_set.take() {
+                    self.selections.remove(&current);
+                    self.selections.save();
+                    self.set_name.clear();
+                }
+                return true
+            },
         }
-        self.refresh.emit(self.get_selected_kana());
+        let selected = self.get_selected_kana();
+        self.selections.set_last(&selected);
+        self.selections.save();
+        self.refresh.emit(selected);
         true
     }
 
@@ -112,6 +318,7 @@ impl Component for KanaSelector {
                 onanimationend=self.onanimationend.clone()
                 onanimationstart=self.onanimationstart.clone()
             >
+                { self.render_sets() }
                 { for self.syllabaries.values().map(|syllabary| syllabary.render()) }
             </div>
         }
@@ -3,10 +3,13 @@ use std::collections::BTreeMap;
 use strum::IntoEnumIterator;
 use yew::prelude::*;
 
-use crate::utils::some_if;
+use crate::{
+    charts::ChartVersion,
+    kana::{Kana, SyllabaryVersion},
+    utils::some_if,
+};
 use super::{
     chart::KanaChart,
-    enums::{ChartVersion, SyllabaryVersion},
     screen::{KanaSelector, KanaMessage},
 };
 
@@ -36,10 +39,14 @@ impl KanaSyllabary {
         }
     }
 
-    pub fn get_selected_kana(&self) -> Vec<String> {
+    pub fn get_selected_kana(&self) -> Vec<Kana> {
         self.charts.values().flat_map(KanaChart::get_selected_kana).collect()
     }
 
+    pub fn set_selected_kana(&mut self, kana: &[Kana]) {
+        self.charts.values_mut().for_each(|chart| chart.set_selected_kana(kana))
+    }
+
     pub fn is_active(&self) -> bool {
         self.charts.values().any(KanaChart::is_active)
     }
@@ -52,10 +59,12 @@ impl KanaSyllabary {
     }
 
     pub fn render(&self) -> Html {
+        let is_active = self.is_active();
         return html! {
             <div class="kana-syllabary">
                 <button
-                    active=some_if!(self.is_active(), "true")
+                    active=some_if!(is_active, "true")
+                    aria-pressed=is_active.to_string()
                     class="syllabary-title"
                     onclick=self.callback.clone()
                 >{ &self.title }</button>
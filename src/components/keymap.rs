@@ -0,0 +1,155 @@
+//! The keyboard shortcuts of the app.
+//!
+//! Every key press is mapped to a Shortcut here, which the App then carries out for the
+//! current screen. Focus is moved between the buttons of the KanaSelector by their position
+//! on the page, so that the arrow keys cross between rows, charts and syllabaries.
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew::utils::document;
+use yew::web_sys::{Element, HtmlElement};
+
+/// The buttons of the KanaSelector which can be focused.
+const SELECTOR_BUTTONS: &str = ".kana-selection-container button:not([void])";
+/// How much more the distance across the direction of movement counts than along it,
+///   which favours the button directly ahead over one which is nearer but off to the side.
+const ALIGNMENT_WEIGHT: f64 = 3.0;
+
+/// The keys of each shortcut and what they do, as listed by the help overlay.
+pub const SHORTCUTS: &[(&str, &str)] = &[
+    ("← ↑ → ↓", "Move between the kana, rows, charts and syllabaries"),
+    ("Space", "Select or deselect the focused kana, row, chart or syllabary"),
+    ("Enter", "Press the focused button, or else start the game, leave a paused game or return from the review"),
+    ("Esc", "Pause or resume the game, or close this help"),
+    ("?", "Show or hide this help"),
+];
+
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Shortcut {
+    Focus(Direction),
+    Toggle,
+    Confirm,
+    Pause,
+    Help,
+}
+
+/// The shortcut of the key press, if it has one.
+/// Only Esc is a shortcut while typing, as every other key is part of the answer.
+/// Enter is left to activate a focused button or link, other than those of the KanaSelector.
+pub fn shortcut(event: &KeyboardEvent) -> Option<Shortcut> {
+    if event.is_composing() || event.ctrl_key() || event.alt_key() || event.meta_key() {
+        return None
+    }
+    let target = event.target().and_then(|target| target.dyn_into::<Element>().ok());
+    let is_typing = target.as_ref()
+        .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA"));
+    let is_activatable = target.as_ref().is_some_and(|element| {
+        matches!(element.tag_name().as_str(), "A" | "BUTTON")
+            && !element.matches(SELECTOR_BUTTONS).unwrap_or(false)
+    });
+    match event.key().as_str() {
+        "Escape" => Some(Shortcut::Pause),
+        _ if is_typing => None,
+        "Enter" if is_activatable => None,
+        "ArrowUp" => Some(Shortcut::Focus(Direction::Up)),
+        "ArrowDown" => Some(Shortcut::Focus(Direction::Down)),
+        "ArrowLeft" => Some(Shortcut::Focus(Direction::Left)),
+        "ArrowRight" => Some(Shortcut::Focus(Direction::Right)),
+        " " => Some(Shortcut::Toggle),
+        "Enter" => Some(Shortcut::Confirm),
+        "?" => Some(Shortcut::Help),
+        _ => None,
+    }
+}
+
+/// Move the focus to the nearest button of the KanaSelector in the given direction,
+///   or to the first button if none of them are focused.
+pub fn move_focus(direction: Direction) {
+    let buttons = selector_buttons();
+    let focused = document().active_element()
+        .and_then(|active| buttons.iter().find(|button| **button == active).cloned());
+    let target = match focused {
+        None => buttons.first(),
+        Some(focused) => {
+            let (x, y) = center(&focused);
+            buttons.iter()
+                .filter_map(|button| {
+                    let (bx, by) = center(button);
+                    let (along, across) = match direction {
+                        Direction::Up => (y - by, bx - x),
+                        Direction::Down => (by - y, bx - x),
+                        Direction::Left => (x - bx, by - y),
+                        Direction::Right => (bx - x, by - y),
+                    };
+                    (along > 0.0).then(|| (button, along + ALIGNMENT_WEIGHT * across.abs()))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(button, _)| button)
+        },
+    };
+    if let Some(target) = target.and_then(|target| target.dyn_ref::<HtmlElement>()) {
+        target.focus().ok();
+    }
+}
+
+/// Press the focused button of the KanaSelector, returning whether there was one.
+pub fn toggle_focused() -> bool {
+    let buttons = selector_buttons();
+    let focused = document().active_element()
+        .filter(|active| buttons.contains(active))
+        .and_then(|active| active.dyn_into::<HtmlElement>().ok());
+    match focused {
+        Some(button) => {
+            button.click();
+            true
+        },
+        None => false,
+    }
+}
+
+/// Render the overlay listing every shortcut.
+pub fn render_help(onclose: Callback<MouseEvent>) -> Html {
+    // Only clicks outside of the panel close the overlay.
+    let onclick_panel = Callback::from(|event: MouseEvent| event.stop_propagation());
+    return html! {
+        <div class="overlay" onclick=onclose.clone()>
+            <div class="overlay-panel" onclick=onclick_panel>
+                <div class="overlay-title">{ "Keyboard Shortcuts" }</div>
+                <table class="shortcuts">
+                    { for SHORTCUTS.iter().map(|(keys, description)| html! {
+                        <tr>
+                            <td><kbd>{ keys }</kbd></td>
+                            <td>{ description }</td>
+                        </tr>
+                    }) }
+                </table>
+                <button onclick=onclose>{ "Close" }</button>
+            </div>
+        </div>
+    }
+}
+
+fn selector_buttons() -> Vec<Element> {
+    let nodes = match document().query_selector_all(SELECTOR_BUTTONS) {
+        Ok(nodes) => nodes,
+        Err(_) => return Vec::new(),
+    };
+    (0..nodes.length())
+        .filter_map(|index| nodes.item(index)?.dyn_into::<Element>().ok())
+        // Buttons of hidden screens have no size.
+        .filter(|element| element.get_bounding_client_rect().width() > 0.0)
+        .collect()
+}
+
+fn center(element: &Element) -> (f64, f64) {
+    let rect = element.get_bounding_client_rect();
+    (rect.x() + rect.width() / 2.0, rect.y() + rect.height() / 2.0)
+}
@@ -1,7 +1,13 @@
 pub use app::App;
 
 mod app;
+mod color;
 mod game;
 mod hide_state;
 mod icons;
 mod kana;
+mod keymap;
+mod review;
+mod settings;
+mod share;
+mod statistics;
@@ -0,0 +1,157 @@
+//! The screen reviewing the session which was just played, shown upon leaving the game.
+use yew::prelude::*;
+use yewtil::NeqAssign;
+
+use crate::{
+    grading::Grade,
+    kana::Kana,
+    statistics::{SessionAnswer, StatisticsHandle},
+};
+use super::{
+    app::{App, AppMessage, Screen},
+    hide_state::HideState,
+};
+
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct ReviewScreenProperties {
+    pub hide_state: HideState,
+    pub screen_type: Screen,
+    /// The seed of the game, from which the kana of its first round were generated.
+    #[prop_or_default]
+    pub seed: u64,
+    pub statistics: StatisticsHandle,
+}
+
+pub struct ReviewScreen {
+    props: ReviewScreenProperties,
+    /// The answers of the session, which are kept while the screen fades out.
+    answers: Vec<SessionAnswer>,
+    /// Milliseconds since the UNIX epoch at which the session started.
+    started: f64,
+    ondrill: Callback<Vec<Kana>>,
+    onanimationstart: Callback<AnimationEvent>,
+    onanimationend: Callback<AnimationEvent>,
+}
+
+impl Component for ReviewScreen {
+    type Message = ();
+    type Properties = ReviewScreenProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let app = link.get_parent().unwrap().clone().downcast::<App>();
+        let onanimationstart = props.screen_type.onanimationstart(&app);
+        let onanimationend = props.screen_type.onanimationend(&app);
+        let ondrill = app.callback(AppMessage::Drill);
+        Self { props, answers: Vec::new(), started: 0.0, ondrill, onanimationstart, onanimationend }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let was_hidden = self.props.hide_state.is_hidden();
+        let should_render = self.props.neq_assign(props);
+        if was_hidden && !self.props.hide_state.is_hidden() {
+            let statistics = self.props.statistics.borrow();
+            self.answers = statistics.session_answers().to_vec();
+            self.started = statistics.session_started();
+        }
+        should_render
+    }
+
+    fn view(&self) -> Html {
+        let missed = self.missed_kana();
+        let is_perfect = missed.is_empty();
+        let ondrill = self.ondrill.reform(move |_: MouseEvent| missed.clone());
+        return html! {
+            <div
+                class="review-container"
+                state=self.props.hide_state.as_string()
+                style=self.props.hide_state.display_none()
+                onanimationend=self.onanimationend.clone()
+                onanimationstart=self.onanimationstart.clone()
+            >
+                { self.render_metrics() }
+                { self.render_mistakes() }
+                <div class="review-actions">
+                    <button
+                        disabled=is_perfect
+                        onclick=ondrill
+                    >{ "Drill These Again" }</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl ReviewScreen {
+
+    /// The distinct kana which were answered incorrectly, in the order they were missed.
+    fn missed_kana(&self) -> Vec<Kana> {
+        let mut missed = Vec::new();
+        for answer in self.answers.iter().filter(|answer| answer.grade.is_wrong()) {
+            let kana = answer.translation.kana;
+            if !missed.contains(&kana) {
+                missed.push(kana);
+            }
+        }
+        missed
+    }
+
+    fn render_metrics(&self) -> Html {
+        let total = self.answers.len();
+        let correct = self.answers.iter().filter(|answer| answer.grade.is_correct()).count();
+        let points: f32 = self.answers.iter().map(|answer| answer.grade.weight()).sum();
+        let duration = self.answers.last().map_or(0.0, |last| last.answered - self.started);
+        let minutes = duration / 60_000.0;
+        let kpm = if minutes > 0.0 { total as f64 / minutes } else { 0.0 };
+        let metric = |label: &str, value: String| html! {
+            <div class="review-metric">
+                <div class="review-metric-value">{ value }</div>
+                <div>{ label }</div>
+            </div>
+        };
+        return html! {
+            <div class="review-metrics">
+                { metric("Correct", format!("{} / {}", correct, total)) }
+                { metric("Accuracy", format!("{:.0}%", points / total.max(1) as f32 * 100.0)) }
+                { metric("Duration", format!("{:.0}:{:02.0}", (duration / 60_000.0).floor(), (duration / 1000.0) % 60.0)) }
+                { metric("Kana per Minute", format!("{:.0}", kpm)) }
+                { metric("Seed", self.props.seed.to_string()) }
+            </div>
+        }
+    }
+
+    fn render_mistakes(&self) -> Html {
+        let mistakes: Vec<&SessionAnswer> = self.answers.iter()
+            .filter(|answer| !answer.grade.is_correct())
+            .collect();
+        if mistakes.is_empty() {
+            return html! { <div class="review-mistakes">{ "No mistakes were made!" }</div> }
+        }
+        return html! {
+            <table class="review-mistakes">
+                <tr>
+                    <th>{ "Kana" }</th>
+                    <th>{ "Expected" }</th>
+                    <th>{ "Typed" }</th>
+                </tr>
+                { for mistakes.iter().map(|answer| {
+                    let class = match answer.grade {
+                        Grade::NearMiss => "near-miss",
+                        _ => "wrong-answer",
+                    };
+                    html! {
+                        <tr>
+                            <td class="review-kana">{ answer.translation.symbol }</td>
+                            <td>{ answer.translation.romanji }</td>
+                            <td class=class>{ &answer.text }</td>
+                        </tr>
+                    }
+                }) }
+            </table>
+        }
+    }
+}
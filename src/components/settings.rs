@@ -0,0 +1,188 @@
+//! The panel of controls for configuring the game, shown alongside the KanaSelector.
+use strum::IntoEnumIterator;
+use yew::prelude::*;
+use yewtil::NeqAssign;
+
+use crate::{
+    kana::Kana,
+    romanization::RomanizationSystem,
+    strokes::drawable_kana,
+    utils::some_if,
+};
+use super::game::{GameMode, GameSettings, SessionType, MAX_FIRST_TO};
+
+
+pub enum SettingsMessage {
+    SetMode(String),
+    SetSession(String),
+    SetFirstTo(String),
+    ToggleAudio,
+    ToggleConfirmAnswers,
+    ToggleEitherSyllabary,
+    ToggleRomanization(RomanizationSystem),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct SettingsPanelProperties {
+    pub settings: GameSettings,
+    pub onchange: Callback<GameSettings>,
+}
+
+pub struct SettingsPanel {
+    props: SettingsPanelProperties,
+    link: ComponentLink<Self>,
+}
+
+impl Component for SettingsPanel {
+    type Message = SettingsMessage;
+    type Properties = SettingsPanelProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self { props, link }
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        let mut settings = self.props.settings.clone();
+        match message {
+            Self::Message::SetMode(mode) => {
+                settings.mode = from_name(&mode).unwrap_or_default();
+            },
+            Self::Message::SetSession(session) => {
+                settings.session = from_name(&session).unwrap_or_default();
+            },
+            Self::Message::SetFirstTo(first_to) => {
+                match first_to.parse::<usize>() {
+                    Ok(first_to) => settings.first_to = first_to.clamp(1, MAX_FIRST_TO),
+                    Err(_) => return false,
+                }
+            },
+            Self::Message::ToggleAudio => {
+                settings.audio ^= true;
+            },
+            Self::Message::ToggleConfirmAnswers => {
+                settings.confirm_answers ^= true;
+            },
+            Self::Message::ToggleEitherSyllabary => {
+                settings.accept_either_syllabary ^= true;
+            },
+            Self::Message::ToggleRomanization(system) => {
+                settings.romanization.toggle(system);
+                // At least one system must remain selected.
+                if settings.romanization.is_empty() {
+                    return false
+                }
+            },
+        }
+        self.props.onchange.emit(settings);
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props.neq_assign(props)
+    }
+
+    fn view(&self) -> Html {
+        let settings = &self.props.settings;
+        let onmode = self.link.batch_callback(|data: ChangeData| match data {
+            ChangeData::Select(select) => Some(SettingsMessage::SetMode(select.value())),
+            _ => None,
+        });
+        let onsession = self.link.batch_callback(|data: ChangeData| match data {
+            ChangeData::Select(select) => Some(SettingsMessage::SetSession(select.value())),
+            _ => None,
+        });
+        // Only a First To round ends at a configurable number of correct answers.
+        let first_to = match settings.session {
+            SessionType::FirstTo => html! {
+                <input
+                    aria-label="Correct answers to finish"
+                    max=MAX_FIRST_TO.to_string()
+                    min="1"
+                    onchange=self.link.batch_callback(|data: ChangeData| match data {
+                        ChangeData::Value(value) => Some(SettingsMessage::SetFirstTo(value)),
+                        _ => None,
+                    })
+                    type="number"
+                    value=settings.first_to.to_string()
+                />
+            },
+            _ => html! {},
+        };
+        let options = match settings.mode {
+            GameMode::Production => html! {
+                <button
+                    active=some_if!(settings.accept_either_syllabary, "true")
+                    onclick=self.link.callback(|_| SettingsMessage::ToggleEitherSyllabary)
+                >{ "Either Syllabary" }</button>
+            },
+            // Only the kana with stroke data are drawn, and the rest of the selection is skipped.
+            GameMode::Writing => html! {
+                <span class="settings-note">
+                    { "Only these kana can be drawn so far: " }
+                    { drawable_kana().map(Kana::symbol).collect::<Vec<_>>().join(" ") }
+                </span>
+            },
+            _ => html! {
+                for RomanizationSystem::iter().map(|system| html! {
+                    <button
+                        active=some_if!(settings.romanization.contains(system), "true")
+                        onclick=self.link.callback(move |_| SettingsMessage::ToggleRomanization(system))
+                    >{ system.to_string() }</button>
+                })
+            },
+        };
+        // The Listening mode is always pronounced.
+        let audio = match settings.mode {
+            GameMode::Listening | GameMode::Writing => html! {},
+            _ => html! {
+                <button
+                    active=some_if!(settings.audio, "true")
+                    onclick=self.link.callback(|_| SettingsMessage::ToggleAudio)
+                >{ "Sound" }</button>
+            },
+        };
+        // Only typed romanji answers are committed before being submitted.
+        let confirm = match settings.mode {
+            GameMode::Choice | GameMode::Production | GameMode::Writing => html! {},
+            _ => html! {
+                <button
+                    active=some_if!(settings.confirm_answers, "true")
+                    onclick=self.link.callback(|_| SettingsMessage::ToggleConfirmAnswers)
+                >{ "Confirm Answers" }</button>
+            },
+        };
+        return html! {
+            <div class="settings-panel">
+                { select(GameMode::iter(), settings.mode, onmode) }
+                { select(SessionType::iter(), settings.session, onsession) }
+                { first_to }
+                { options }
+                { audio }
+                { confirm }
+            </div>
+        }
+    }
+}
+
+/// Render a <select> element with an option for each of the given values.
+fn select<T>(values: impl Iterator<Item=T>, selected: T, onchange: Callback<ChangeData>) -> Html
+where
+    T: ToString + PartialEq,
+{
+    return html! {
+        <select onchange=onchange>
+            { for values.map(|value| {
+                let name = value.to_string();
+                let is_selected = value == selected;
+                html! {
+                    <option value=name.clone() selected=is_selected>{ name }</option>
+                }
+            }) }
+        </select>
+    }
+}
+
+/// Find the enum variant whose Display name is the given name.
+fn from_name<T: IntoEnumIterator + ToString>(name: &str) -> Option<T> {
+    T::iter().find(|value| value.to_string() == name)
+}
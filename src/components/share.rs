@@ -0,0 +1,267 @@
+//! The shareable link of the app.
+//!
+//! The kana selection and game settings are encoded into the fragment of the URL, so that
+//! opening a copy of the link restores them. The `s` parameter of the fragment holds the
+//! unpadded base64url encoding of the bytes:
+//!     [version, mode, session, options (2 bytes, little-endian), first_to, kana...]
+//! where the modes, sessions and romanization systems are given fixed IDs by `mode_id`,
+//! `session_id` and `system_flag` which must never be reused, and the kana are a bitset with
+//! a bit for every cell of the charts in the order that they are laid out
+//! (syllabary → chart → row → button).
+//!
+//! The optional `seed` parameter holds the seed of the game as a decimal number, so that
+//! everyone opening the link is drilled with the same kana in the same order.
+use strum::IntoEnumIterator;
+use wasm_bindgen::JsValue;
+use yew::utils::window;
+
+use crate::{
+    charts::{chart_layout, ChartVersion},
+    kana::{Kana, SyllabaryVersion},
+    romanization::{RomanizationSystem, RomanizationSystems},
+};
+use super::game::{GameMode, GameSettings, SessionType, MAX_FIRST_TO};
+
+/// The version of the encoding, which is bumped whenever the bytes change meaning.
+/// The kana bitset follows the layout of charts.csv, so this must also be bumped whenever
+/// a cell of charts.csv is added, removed or moved, or old links will select other kana.
+const FORMAT_VERSION: u8 = 2;
+/// The fragment parameter which holds the encoded state.
+const STATE_PARAMETER: &str = "s";
+/// The fragment parameter which holds the seed of the game.
+const SEED_PARAMETER: &str = "seed";
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+/// The option flags which follow those of the romanization systems.
+const AUDIO_FLAG: u16 = 1 << 4;
+const EITHER_SYLLABARY_FLAG: u16 = 1 << 5;
+const CONFIRM_ANSWERS_FLAG: u16 = 1 << 6;
+
+
+/// The kana selection and game settings held by a link.
+pub struct SharedState {
+    pub kana: Vec<Kana>,
+    pub settings: GameSettings,
+}
+
+/// The state held by the fragment of the current URL, if it holds a valid one.
+pub fn read_fragment() -> Option<SharedState> {
+    let hash = window().location().hash().ok()?;
+    let value = fragment_parameter(&hash, STATE_PARAMETER)?;
+    decode(&base64url_decode(value)?)
+}
+
+/// The seed held by the fragment of the current URL, if it holds a valid one.
+pub fn read_seed() -> Option<u64> {
+    let hash = window().location().hash().ok()?;
+    fragment_parameter(&hash, SEED_PARAMETER)?.parse().ok()
+}
+
+/// Replace the fragment of the current URL with the given state,
+///   without adding an entry to the browser's history.
+pub fn write_fragment(kana: &[Kana], settings: &GameSettings, seed: Option<u64>) {
+    let mut fragment = format!("#{}={}", STATE_PARAMETER, base64url_encode(&encode(kana, settings)));
+    if let Some(seed) = seed {
+        fragment.push_str(&format!("&{}={}", SEED_PARAMETER, seed));
+    }
+    if let Ok(history) = window().history() {
+        history.replace_state_with_url(&JsValue::NULL, "", Some(&fragment)).ok();
+    }
+}
+
+/// The value of a parameter within a fragment of the form "#key=value&key=value".
+fn fragment_parameter<'a>(hash: &'a str, key: &str) -> Option<&'a str> {
+    hash.trim_start_matches('#')
+        .split('&')
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|&(name, _)| name == key)
+        .map(|(_, value)| value)
+}
+
+/// Every cell of the charts in the order that they are laid out, where empty cells are None.
+fn chart_cells() -> impl Iterator<Item=Option<Kana>> {
+    SyllabaryVersion::iter()
+        .flat_map(|syllabary| ChartVersion::iter().map(move |chart| chart_layout(chart, syllabary)))
+        .flat_map(|rows| rows.iter().flat_map(|row| row.iter().copied()))
+}
+
+fn encode(kana: &[Kana], settings: &GameSettings) -> Vec<u8> {
+    let mut options = settings.romanization.iter().fold(0, |options, system| options | system_flag(system));
+    let flags = [
+        (settings.audio, AUDIO_FLAG),
+        (settings.accept_either_syllabary, EITHER_SYLLABARY_FLAG),
+        (settings.confirm_answers, CONFIRM_ANSWERS_FLAG),
+    ];
+    for (is_set, flag) in flags {
+        if is_set {
+            options |= flag;
+        }
+    }
+    let [options_low, options_high] = options.to_le_bytes();
+
+    let mut bytes = vec![
+        FORMAT_VERSION,
+        mode_id(settings.mode),
+        session_id(settings.session),
+        options_low,
+        options_high,
+        settings.first_to.min(u8::MAX as usize) as u8,
+    ];
+    for (index, cell) in chart_cells().enumerate() {
+        if index % 8 == 0 {
+            bytes.push(0);
+        }
+        if cell.is_some_and(|cell| kana.contains(&cell)) {
+            *bytes.last_mut().unwrap() |= 1 << (index % 8);
+        }
+    }
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> Option<SharedState> {
+    let (mode, session, options, first_to, bitset) = match bytes {
+        [FORMAT_VERSION, mode, session, low, high, first_to, bitset @ ..] => {
+            (*mode, *session, u16::from_le_bytes([*low, *high]), *first_to as usize, bitset)
+        },
+        _ => return None,
+    };
+    let settings = GameSettings {
+        mode: GameMode::iter().find(|&m| mode_id(m) == mode)?,
+        session: SessionType::iter().find(|&s| session_id(s) == session)?,
+        first_to: first_to.clamp(1, MAX_FIRST_TO),
+        audio: options & AUDIO_FLAG != 0,
+        accept_either_syllabary: options & EITHER_SYLLABARY_FLAG != 0,
+        confirm_answers: options & CONFIRM_ANSWERS_FLAG != 0,
+        romanization: RomanizationSystem::iter()
+            .filter(|&system| options & system_flag(system) != 0)
+            .collect::<RomanizationSystems>(),
+    };
+    let kana = chart_cells()
+        .enumerate()
+        .filter(|&(index, _)| bitset.get(index / 8).is_some_and(|byte| byte & 1 << (index % 8) != 0))
+        .filter_map(|(_, cell)| cell)
+        .collect();
+    Some(SharedState { kana, settings })
+}
+
+/// The ID of each game mode. New modes are given new IDs, rather than reusing those of old ones.
+fn mode_id(mode: GameMode) -> u8 {
+    match mode {
+        GameMode::Recognition => 0,
+        GameMode::Choice => 1,
+        GameMode::Confusables => 2,
+        GameMode::Production => 3,
+        GameMode::Words => 4,
+        GameMode::Listening => 5,
+        GameMode::Writing => 6,
+    }
+}
+
+/// The ID of each session type. New sessions are given new IDs, rather than reusing those of old ones.
+fn session_id(session: SessionType) -> u8 {
+    match session {
+        SessionType::Endless => 0,
+        SessionType::Sprint => 1,
+        SessionType::FirstTo => 2,
+        SessionType::SuddenDeath => 3,
+    }
+}
+
+/// The option flag of each romanization system.
+fn system_flag(system: RomanizationSystem) -> u16 {
+    match system {
+        RomanizationSystem::Hepburn => 1 << 0,
+        RomanizationSystem::KunreiShiki => 1 << 1,
+        RomanizationSystem::NihonShiki => 1 << 2,
+        RomanizationSystem::Ime => 1 << 3,
+    }
+}
+
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate()
+            .fold(0u32, |bits, (index, &byte)| bits | (byte as u32) << (16 - 8 * index));
+        // A chunk of n bytes fills n + 1 characters.
+        for index in 0..=chunk.len() {
+            text.push(BASE64URL[(bits >> (18 - 6 * index) & 0x3F) as usize] as char);
+        }
+    }
+    text
+}
+
+fn base64url_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        let mut bits = 0u32;
+        for (index, character) in chunk.iter().enumerate() {
+            let value = BASE64URL.iter().position(|c| c == character)?;
+            bits |= (value as u32) << (18 - 6 * index);
+        }
+        // A chunk of n characters holds n - 1 bytes, so a lone character holds none.
+        if chunk.len() < 2 {
+            return None
+        }
+        for index in 0..chunk.len() - 1 {
+            bytes.push((bits >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> GameSettings {
+        GameSettings {
+            mode: GameMode::Production,
+            session: SessionType::SuddenDeath,
+            first_to: 30,
+            accept_either_syllabary: true,
+            romanization: [RomanizationSystem::Hepburn, RomanizationSystem::Ime].into_iter().collect(),
+            ..GameSettings::default()
+        }
+    }
+
+    #[test]
+    fn base64url_round_trips() {
+        assert_eq!(base64url_encode(b"hello"), "aGVsbG8");
+        for length in 0..10 {
+            let bytes: Vec<u8> = (0..length).map(|index| (index * 37 + 200) as u8).collect();
+            assert_eq!(base64url_decode(&base64url_encode(&bytes)), Some(bytes));
+        }
+    }
+
+    #[test]
+    fn base64url_rejects_other_characters() {
+        assert_eq!(base64url_decode("aGV+bG8"), None);
+        assert_eq!(base64url_decode("a"), None);
+    }
+
+    #[test]
+    fn state_round_trips() {
+        let kana: Vec<Kana> = chart_cells().flatten().step_by(3).collect();
+        for mode in GameMode::iter() {
+            for session in SessionType::iter() {
+                let settings = GameSettings { mode, session, audio: true, confirm_answers: true, ..settings() };
+                let shared = decode(&encode(&kana, &settings)).expect("state could not be decoded");
+                assert_eq!(shared.settings, settings);
+                assert_eq!(shared.kana, kana);
+            }
+        }
+    }
+
+    #[test]
+    fn encoding_is_stable() {
+        assert_eq!(encode(&[], &settings())[..6], [FORMAT_VERSION, 3, 3, 0b0010_1001, 0, 30]);
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_ids() {
+        assert!(decode(&[0, 0, 0, 0, 0, 25]).is_none());
+        assert!(decode(&[1, 0, 0, 0b1, 0b1]).is_none());
+        assert!(decode(&[FORMAT_VERSION, 99, 0, 0, 0, 25]).is_none());
+        assert!(decode(&[FORMAT_VERSION, 0, 99, 0, 0, 25]).is_none());
+    }
+}
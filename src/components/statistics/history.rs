@@ -0,0 +1,77 @@
+use yew::prelude::*;
+
+use crate::{kana::Kana, statistics::KanaStatistics};
+use super::super::color::{GREEN, RED};
+
+/// The number of common wrong answers to list.
+const WRONG_ANSWER_LIMIT: usize = 5;
+
+
+/// Render the recorded history of a single kana.
+pub fn render_history(kana: Kana, statistics: Option<&KanaStatistics>) -> Html {
+    let statistics = match statistics {
+        Some(statistics) => statistics,
+        None => return html! {
+            <div class="kana-history">
+                <div class="kana-history-symbol">{ kana.symbol() }</div>
+                <div>{ "This kana has not been practiced yet." }</div>
+            </div>
+        },
+    };
+
+    let accuracy = statistics.accuracy().unwrap_or_default();
+    let median = statistics.median_response_time()
+        .map_or_else(|| "-".into(), |time| format!("{:.2}s", time / 1000.0));
+    let wrong_answers = statistics.common_wrong_answers();
+    let confusions = statistics.common_confusions();
+    return html! {
+        <div class="kana-history">
+            <div class="kana-history-symbol">{ kana.symbol() }</div>
+            <div class="kana-history-metrics">
+                <div>{ format!("Attempts: {}", statistics.attempts) }</div>
+                <div>{ format!("Accuracy: {:.0}%", accuracy * 100.0) }</div>
+                <div>{ format!("Median Time: {}", median) }</div>
+            </div>
+            <div class="kana-history-wrong">
+                { "Common Mistakes: " }
+                {
+                    if wrong_answers.is_empty() {
+                        html! { "None" }
+                    } else {
+                        html! {
+                            for wrong_answers.iter().take(WRONG_ANSWER_LIMIT).map(|(answer, count)| html! {
+                                <span class="wrong-answer">{ format!("{} ×{}", answer, count) }</span>
+                            })
+                        }
+                    }
+                }
+            </div>
+            <div class="kana-history-wrong">
+                { "Mistaken For: " }
+                {
+                    if confusions.is_empty() {
+                        html! { "None" }
+                    } else {
+                        html! {
+                            for confusions.iter().take(WRONG_ANSWER_LIMIT).map(|(kana, count)| html! {
+                                <span class="wrong-answer">{ format!("{} ×{}", kana, count) }</span>
+                            })
+                        }
+                    }
+                }
+            </div>
+            <div class="kana-history-trend">
+                { for statistics.sessions.iter().map(|session| {
+                    let accuracy = session.accuracy();
+                    let style = format!(
+                        "height: {}%; background: {};",
+                        (accuracy * 100.0).max(5.0),
+                        RED.interpolate_to(&GREEN, accuracy.powi(4)).as_string(),
+                    );
+                    let title = format!("{} / {}", session.correct, session.attempts);
+                    html! { <div class="trend-bar" style=style title=title/> }
+                }) }
+            </div>
+        </div>
+    }
+}
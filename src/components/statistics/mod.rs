@@ -0,0 +1,4 @@
+mod history;
+mod screen;
+
+pub use screen::StatisticsScreen;
@@ -0,0 +1,153 @@
+use strum::IntoEnumIterator;
+use yew::prelude::*;
+use yewtil::NeqAssign;
+
+use crate::{
+    charts::{chart_layout, ChartVersion},
+    kana::{Kana, SyllabaryVersion},
+    statistics::{KanaStatistics, StatisticsHandle},
+    utils::some_if,
+};
+use super::super::{
+    app::{App, Screen},
+    color::{Color, GREEN, RED},
+    hide_state::HideState,
+};
+use super::history::render_history;
+
+/// Median response times (in milliseconds) at or below this are considered fast.
+const FAST_RESPONSE: f64 = 1000.0;
+/// Median response times (in milliseconds) at or above this are considered slow.
+const SLOW_RESPONSE: f64 = 4000.0;
+/// The lightness that the cells of slowly answered kana are shaded towards.
+const SLOW_LIGHTNESS: u8 = 25;
+
+
+pub enum StatisticsMessage {
+    Select(Kana),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct StatisticsScreenProperties {
+    pub hide_state: HideState,
+    pub screen_type: Screen,
+    pub statistics: StatisticsHandle,
+}
+
+pub struct StatisticsScreen {
+    props: StatisticsScreenProperties,
+    link: ComponentLink<Self>,
+    selected: Option<Kana>,
+    onanimationstart: Callback<AnimationEvent>,
+    onanimationend: Callback<AnimationEvent>,
+}
+
+impl Component for StatisticsScreen {
+    type Message = StatisticsMessage;
+    type Properties = StatisticsScreenProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let app = link.get_parent().unwrap().clone().downcast::<App>();
+        let onanimationstart = props.screen_type.onanimationstart(&app);
+        let onanimationend = props.screen_type.onanimationend(&app);
+        Self { props, link, selected: None, onanimationstart, onanimationend }
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Self::Message::Select(kana) => {
+                self.selected.neq_assign(Some(kana))
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props.neq_assign(props)
+    }
+
+    fn view(&self) -> Html {
+        let history = match self.selected {
+            Some(kana) => render_history(kana, self.props.statistics.borrow().get(kana)),
+            None => html! {
+                <div class="kana-history">{ "Select a kana to view its history." }</div>
+            },
+        };
+        return html! {
+            <div
+                class="statistics-container"
+                state=self.props.hide_state.as_string()
+                style=self.props.hide_state.display_none()
+                onanimationend=self.onanimationend.clone()
+                onanimationstart=self.onanimationstart.clone()
+            >
+                { history }
+                { for SyllabaryVersion::iter().map(|syllabary| self.render_syllabary(syllabary)) }
+            </div>
+        }
+    }
+}
+
+impl StatisticsScreen {
+
+    fn render_syllabary(&self, syllabary: SyllabaryVersion) -> Html {
+        return html! {
+            <div class="kana-syllabary">
+                <div class="syllabary-title">{ syllabary.to_string() }</div>
+                <div class="kana-chart-collection">
+                    { for ChartVersion::iter().map(|chart| self.render_chart(chart, syllabary)) }
+                </div>
+            </div>
+        }
+    }
+
+    fn render_chart(&self, chart: ChartVersion, syllabary: SyllabaryVersion) -> Html {
+        return html! {
+            <div class="kana-chart">
+                <div class="chart-title">{ chart.to_string() }</div>
+                { for chart_layout(chart, syllabary).iter().map(|row| html! {
+                    <div class="kana-row">
+                        { for row.iter().map(|&kana| self.render_cell(kana)) }
+                    </div>
+                }) }
+            </div>
+        }
+    }
+
+    fn render_cell(&self, kana: Option<Kana>) -> Html {
+        let kana = match kana {
+            Some(kana) => kana,
+            // As with the KanaButton, void cells are given content to keep their size consistent.
+            None => return html! { <button void="true">{ "ん" }</button> },
+        };
+        let statistics = self.props.statistics.borrow();
+        let statistics = statistics.get(kana);
+
+        let style = statistics
+            .and_then(cell_color)
+            .map(|color| format!("background: {};", color.as_string()));
+        let title = statistics.and_then(|s| {
+            s.accuracy().map(|accuracy| format!("{:.0}% of {} attempts", accuracy * 100.0, s.attempts))
+        });
+        let onclick = self.link.callback(move |_| StatisticsMessage::Select(kana));
+        let selected = self.selected == Some(kana);
+        return html! {
+            <button
+                active=some_if!(selected, "true")
+                onclick=onclick
+                style=style
+                title=title
+            >{ kana.symbol() }</button>
+        }
+    }
+}
+
+/// The color of a chart cell, where the hue reflects the accuracy of the kana
+///   and slow median response times darken the cell.
+fn cell_color(statistics: &KanaStatistics) -> Option<Color> {
+    let accuracy = statistics.accuracy()?;
+    let color = RED.interpolate_to(&GREEN, accuracy.powi(4));
+    let slowness = statistics.median_response_time()
+        .map_or(0.0, |time| (time - FAST_RESPONSE) / (SLOW_RESPONSE - FAST_RESPONSE))
+        .clamp(0.0, 1.0);
+    Some(color.shade_to(SLOW_LIGHTNESS, slowness as f32))
+}
@@ -0,0 +1,80 @@
+//! This file is where the kana which are easily mistaken for one another are accessed.
+//!
+//! The CONFUSABLE_GROUPS table is generated by the build.rs script from the curated
+//! confusables.csv file at the project root, and stored within the included
+//! confusables_codegen.rs file.
+//!
+//! The curated table is complemented by the ConfusionMatrix of the player's own mistakes,
+//! which is recorded within their statistics.
+include!("confusables_codegen.rs");  // CONFUSABLE_GROUPS
+
+use std::collections::BTreeMap;
+
+use strum::IntoEnumIterator;
+
+use crate::{kana::Kana, romanization::RomanizationSystem};
+
+/// The number of times each kana has been mistaken for another kana,
+///   keyed by (the kana, the kana it was mistaken for).
+pub type ConfusionMatrix = BTreeMap<(Kana, Kana), u32>;
+
+/// Enumeration of the reasons why kana are mistaken for one another.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Confusion {
+    /// The kana look alike, i.e. シ and ツ.
+    Shape,
+    /// The kana sound alike, i.e. し and ち.
+    Sound,
+}
+
+struct ConfusableGroup {
+    kana: &'static [Kana],
+    reason: Confusion,
+}
+
+/// The kana which are easily mistaken for the given kana, and why,
+///   in the order they appear within the table.
+pub fn confusables(kana: Kana) -> Vec<(Kana, Confusion)> {
+    let mut confusables: Vec<(Kana, Confusion)> = Vec::new();
+    for group in CONFUSABLE_GROUPS.iter().filter(|group| group.kana.contains(&kana)) {
+        for &other in group.kana {
+            if other != kana && !confusables.iter().any(|&(k, _)| k == other) {
+                confusables.push((other, group.reason));
+            }
+        }
+    }
+    confusables
+}
+
+/// Every pair of kana which share a group within the curated table.
+pub fn confusable_pairs() -> impl Iterator<Item=(Kana, Kana)> {
+    CONFUSABLE_GROUPS.iter().flat_map(|group| {
+        group.kana.iter().enumerate().flat_map(move |(index, &a)| {
+            group.kana[index + 1..].iter().map(move |&b| (a, b))
+        })
+    })
+}
+
+/// Every kana which looks alike another kana within the curated table.
+pub fn look_alikes() -> impl Iterator<Item=Kana> {
+    CONFUSABLE_GROUPS.iter()
+        .filter(|group| group.reason == Confusion::Shape)
+        .flat_map(|group| group.kana.iter().copied())
+}
+
+/// The kana which the given answer of a kana belongs to instead, if any,
+///   i.e. answering "tsu" for シ mistakes it for ツ.
+/// The answer may be the romanji of the other kana within any romanization system,
+///   or the other kana itself (as given within the Production mode).
+pub fn mistaken_for(kana: Kana, text: &str) -> Option<Kana> {
+    if let Some(other) = Kana::from_symbol(text) {
+        return Some(other).filter(|&other| other != kana)
+    }
+    // The romanji of both syllabaries coincide, so only the syllabary of the kana is considered.
+    Kana::iter()
+        .filter(|&other| other != kana && other.syllabary() == kana.syllabary())
+        .find(|other| {
+            let translation = other.translation();
+            RomanizationSystem::iter().any(|system| translation.spellings(system).contains(&text))
+        })
+}
@@ -0,0 +1,141 @@
+//! The grading of answers.
+//!
+//! Besides being correct or wrong, an answer may be a near-miss which is given partial
+//! credit: a spelling of the kana within a romanization system the player has not chosen,
+//! a slip of a single keystroke onto a neighbouring key (i.e. "ks" for "ka"), or the kana
+//! of the other syllabary when either syllabary is not accepted. A slip which spells another
+//! kana (i.e. "ko" for き) is not a near-miss, as the player may well have confused the two.
+use crate::matcher::Match;
+
+/// The keys of a QWERTY keyboard by row, along with how far each row is offset
+///   from the row above it in widths of a key.
+const KEYBOARD_ROWS: &[(&str, f32)] = &[
+    ("1234567890-", 0.0),
+    ("qwertyuiop", 0.5),
+    ("asdfghjkl;", 0.75),
+    ("zxcvbnm,./", 1.25),
+];
+
+
+/// Enumeration of the grades an answer may be given.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Grade {
+    Correct,
+    NearMiss,
+    Wrong,
+}
+
+impl From<bool> for Grade {
+    fn from(is_correct: bool) -> Self {
+        match is_correct {
+            true => Self::Correct,
+            false => Self::Wrong,
+        }
+    }
+}
+
+impl Grade {
+
+    /// The credit given for an answer of this grade, from none to full.
+    pub fn weight(self) -> f32 {
+        match self {
+            Self::Correct => 1.0,
+            Self::NearMiss => 0.5,
+            Self::Wrong => 0.0,
+        }
+    }
+
+    pub fn is_correct(self) -> bool {
+        self == Self::Correct
+    }
+
+    pub fn is_wrong(self) -> bool {
+        self == Self::Wrong
+    }
+
+    /// The CSS class which colors an answer of this grade.
+    pub fn class(self) -> &'static str {
+        match self {
+            Self::Correct => "green",
+            Self::NearMiss => "orange",
+            Self::Wrong => "red",
+        }
+    }
+}
+
+/// Match the beginning of the text against a spelling typed with a single slip
+///   onto a neighbouring key, i.e. "ks" for "ka".
+/// The text is Pending while it is shorter than the spelling.
+pub fn match_slip(text: &str, spelling: &str) -> Match {
+    let mut slipped = false;
+    let mut typed = text.char_indices();
+    for expected in spelling.chars() {
+        let (index, c) = match typed.next() {
+            Some(typed) => typed,
+            None => return Match::Pending(None),
+        };
+        if c != expected {
+            if slipped || !is_adjacent(c, expected) {
+                return Match::Mismatch(index + c.len_utf8())
+            }
+            slipped = true;
+        }
+    }
+    match (slipped, typed.next()) {
+        // The spelling itself is not a slip.
+        (false, _) => Match::Mismatch(spelling.len()),
+        (true, Some((index, _))) => Match::Complete(index),
+        (true, None) => Match::Complete(text.len()),
+    }
+}
+
+/// Whether the keys of the characters neighbour one another on a QWERTY keyboard.
+fn is_adjacent(a: char, b: char) -> bool {
+    match (key_position(a), key_position(b)) {
+        (Some((row_a, column_a)), Some((row_b, column_b))) => {
+            a != b && row_a.abs_diff(row_b) <= 1 && (column_a - column_b).abs() <= 1.0
+        },
+        _ => false,
+    }
+}
+
+fn key_position(c: char) -> Option<(usize, f32)> {
+    KEYBOARD_ROWS.iter().enumerate().find_map(|(row, (keys, offset))| {
+        keys.chars().position(|key| key == c).map(|column| (row, column as f32 + offset))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slips_are_matched_against_the_spelling() {
+        assert_eq!(match_slip("k", "ka"), Match::Pending(None));
+        assert_eq!(match_slip("ks", "ka"), Match::Complete(2));
+        assert_eq!(match_slip("ksi", "ka"), Match::Complete(2));
+        assert_eq!(match_slip("sj", "shi"), Match::Pending(None));
+        assert_eq!(match_slip("sji", "shi"), Match::Complete(3));
+    }
+
+    #[test]
+    fn only_a_single_slip_onto_a_neighbouring_key_is_matched() {
+        assert_eq!(match_slip("ka", "ka"), Match::Mismatch(2));
+        assert_eq!(match_slip("kp", "ka"), Match::Mismatch(2));
+        assert_eq!(match_slip("js", "ka"), Match::Mismatch(2));
+        assert_eq!(match_slip("kaé", "kai"), Match::Mismatch(4));
+    }
+
+    #[test]
+    fn neighbouring_keys_account_for_the_offset_of_each_row() {
+        assert!(is_adjacent('a', 's'));
+        assert!(is_adjacent('a', 'q'));
+        assert!(is_adjacent('a', 'w'));
+        assert!(is_adjacent('a', 'z'));
+        assert!(is_adjacent('k', 'm'));
+        assert!(!is_adjacent('a', 'x'));
+        assert!(!is_adjacent('k', 'n'));
+        assert!(!is_adjacent('a', 'a'));
+        assert!(!is_adjacent('a', 'ā'));
+    }
+}
@@ -0,0 +1,101 @@
+//! This file is where the Kana type is defined.
+//!
+//! Every kana within the kana_to_romanji.csv file is identified by a Kana,
+//! which carries the position of the kana within the syllabaries.
+//! The KANA table and SYMBOL_TO_KANA map are generated by the build.rs script
+//! and stored within the included kana_codegen.rs file.
+include!("kana_codegen.rs");  // KANA, SYMBOL_TO_KANA
+
+use strum::{Display, EnumIter};
+
+use crate::{
+    charts::ChartVersion,
+    translations::{kana_translation, KanaTranslation},
+};
+
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Display, EnumIter)]
+pub enum SyllabaryVersion {
+    Hirigana,
+    Katakana,
+}
+
+/// A single kana, i.e. "か", "ガ" or "きゃ".
+///
+/// Outside of the generated tables, a Kana can only be obtained from its symbol
+///   or by iterating over all of them, and so every Kana is guaranteed to have a translation.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Kana(pub(crate) u16);
+
+/// The properties of a single Kana, as generated from the kana_to_romanji.csv file.
+struct KanaData {
+    symbol: &'static str,
+    syllabary: SyllabaryVersion,
+    chart: Option<ChartVersion>,
+    /// The gojūon row and column of the kana.
+    position: Option<(u8, u8)>,
+    base: Option<Kana>,
+    yoon: Option<(Kana, Kana)>,
+}
+
+impl Kana {
+
+    /// Look up the Kana with the given symbol.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        SYMBOL_TO_KANA.get(symbol).copied()
+    }
+
+    /// Iterate over every Kana in the order of the kana_to_romanji.csv file.
+    pub fn iter() -> impl Iterator<Item=Self> {
+        (0..KANA.len() as u16).map(Self)
+    }
+
+    pub fn symbol(self) -> &'static str {
+        self.data().symbol
+    }
+
+    pub fn syllabary(self) -> SyllabaryVersion {
+        self.data().syllabary
+    }
+
+    /// The chart the kana belongs to. Marks such as the sokuon (っ) belong to none.
+    pub fn chart(self) -> Option<ChartVersion> {
+        self.data().chart
+    }
+
+    /// The gojūon row of the kana, counting from the あ row.
+    /// Kana with a dakuten, handakuten or yōon share the row of their unvoiced consonant.
+    pub fn row(self) -> Option<u8> {
+        self.data().position.map(|(row, _)| row)
+    }
+
+    /// The gojūon column of the kana, counting from the あ column.
+    pub fn column(self) -> Option<u8> {
+        self.data().position.map(|(_, column)| column)
+    }
+
+    /// The kana without its dakuten or handakuten, i.e. "か" for "が" and "ひゃ" for "ぴゃ".
+    pub fn base(self) -> Option<Self> {
+        self.data().base
+    }
+
+    /// The kana that a yōon is composed of, where the small kana is given by
+    ///   its full sized counterpart, i.e. ("き", "や") for "きゃ".
+    pub fn yoon(self) -> Option<(Self, Self)> {
+        self.data().yoon
+    }
+
+    pub fn translation(self) -> &'static KanaTranslation {
+        kana_translation(self)
+    }
+
+    fn data(self) -> &'static KanaData {
+        &KANA[self.0 as usize]
+    }
+}
+
+impl std::fmt::Display for Kana {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
@@ -1,7 +1,19 @@
+pub mod audio;
+pub mod charts;
 pub mod components;
+pub mod confusables;
+pub mod grading;
+pub mod kana;
+pub mod matcher;
+pub mod romanization;
+pub mod scheduler;
+pub mod selections;
+pub mod statistics;
+pub mod strokes;
 mod syllabograms;
 pub mod translations;
 mod utils;
+pub mod words;
 
 pub use components::App;
 use syllabograms::Syllabograms;
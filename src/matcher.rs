@@ -0,0 +1,123 @@
+//! The matching of typed romanji against the accepted spellings of a syllabogram.
+//!
+//! The spellings are arranged into a prefix trie which is walked along the typed text,
+//! so that an answer is only committed once the text can no longer be extended into a
+//! longer spelling. This allows spellings which begin one another to all be accepted,
+//! i.e. both "n" and "nn" for ん, where "n" is committed once the next character is typed.
+
+
+/// The result of matching the beginning of a text against the spellings.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Match {
+    /// The whole text could still be extended into a spelling.
+    /// Holds the length of the longest spelling the text already begins with, if any.
+    Pending(Option<usize>),
+    /// The text begins with a spelling of the given length which cannot be extended.
+    Complete(usize),
+    /// The text up to the given length is not the beginning of any spelling.
+    Mismatch(usize),
+}
+
+#[derive(Default)]
+struct Node {
+    children: Vec<(char, usize)>,
+    /// Whether the path to this node spells a whole spelling.
+    is_spelling: bool,
+}
+
+/// A prefix trie of the accepted spellings of a syllabogram.
+pub struct SpellingTrie {
+    nodes: Vec<Node>,
+}
+
+impl SpellingTrie {
+
+    pub fn new<'a>(spellings: impl IntoIterator<Item=&'a str>) -> Self {
+        let mut trie = Self { nodes: vec![Node::default()] };
+        for spelling in spellings {
+            let mut node = 0;
+            for c in spelling.chars() {
+                node = match trie.child(node, c) {
+                    Some(child) => child,
+                    None => {
+                        trie.nodes.push(Node::default());
+                        let child = trie.nodes.len() - 1;
+                        trie.nodes[node].children.push((c, child));
+                        child
+                    },
+                };
+            }
+            trie.nodes[node].is_spelling = true;
+        }
+        trie
+    }
+
+    /// Match the beginning of the text against the spellings, preferring the longest spelling.
+    pub fn matches(&self, text: &str) -> Match {
+        let mut node = 0;
+        let mut longest = None;
+        for (index, c) in text.char_indices() {
+            node = match self.child(node, c) {
+                Some(child) => child,
+                None => return match longest {
+                    Some(length) => Match::Complete(length),
+                    None => Match::Mismatch(index + c.len_utf8()),
+                },
+            };
+            if self.nodes[node].is_spelling {
+                longest = Some(index + c.len_utf8());
+            }
+        }
+        match (self.nodes[node].children.is_empty(), longest) {
+            (true, Some(length)) => Match::Complete(length),
+            _ => Match::Pending(longest),
+        }
+    }
+
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        self.nodes[node].children.iter()
+            .find(|&&(child_char, _)| child_char == c)
+            .map(|&(_, child)| child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spellings_which_begin_one_another_are_all_accepted() {
+        let trie = SpellingTrie::new(["n", "nn"]);
+        assert_eq!(trie.matches("n"), Match::Pending(Some(1)));
+        assert_eq!(trie.matches("na"), Match::Complete(1));
+        assert_eq!(trie.matches("nn"), Match::Complete(2));
+        assert_eq!(trie.matches("nnk"), Match::Complete(2));
+    }
+
+    #[test]
+    fn every_spelling_is_accepted() {
+        let trie = SpellingTrie::new(["shi", "si"]);
+        assert_eq!(trie.matches("s"), Match::Pending(None));
+        assert_eq!(trie.matches("sh"), Match::Pending(None));
+        assert_eq!(trie.matches("shi"), Match::Complete(3));
+        assert_eq!(trie.matches("si"), Match::Complete(2));
+        assert_eq!(trie.matches("sika"), Match::Complete(2));
+    }
+
+    #[test]
+    fn wrong_prefixes_are_mismatched() {
+        let trie = SpellingTrie::new(["shi", "si"]);
+        assert_eq!(trie.matches("k"), Match::Mismatch(1));
+        assert_eq!(trie.matches("sa"), Match::Mismatch(2));
+        assert_eq!(trie.matches("shu"), Match::Mismatch(3));
+    }
+
+    #[test]
+    fn lengths_fall_on_char_boundaries() {
+        let trie = SpellingTrie::new(["ō", "ou"]);
+        assert_eq!(trie.matches("ō"), Match::Complete(2));
+        assert_eq!(trie.matches("ōka"), Match::Complete(2));
+        assert_eq!(trie.matches("é"), Match::Mismatch(2));
+        assert_eq!(trie.matches("oé"), Match::Mismatch(3));
+    }
+}
@@ -0,0 +1,62 @@
+//! The systems of romanization which answers may be given in.
+//!
+//! The spellings of each kana within each system are recorded within the
+//! kana_to_romanji.csv file, and are accessed through `KanaTranslation::spellings`.
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Display, EnumIter)]
+pub enum RomanizationSystem {
+    Hepburn,
+    #[strum(serialize = "Kunrei-shiki")]
+    KunreiShiki,
+    #[strum(serialize = "Nihon-shiki")]
+    NihonShiki,
+    /// The spellings accepted by common input method editors.
+    #[strum(serialize = "IME")]
+    Ime,
+}
+
+impl RomanizationSystem {
+    fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+
+/// A set of romanization systems.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RomanizationSystems(u8);
+
+impl Default for RomanizationSystems {
+
+    /// By default, the spellings of every system are accepted.
+    fn default() -> Self {
+        RomanizationSystem::iter().collect()
+    }
+}
+
+impl FromIterator<RomanizationSystem> for RomanizationSystems {
+    fn from_iter<T: IntoIterator<Item=RomanizationSystem>>(iter: T) -> Self {
+        Self(iter.into_iter().fold(0, |bits, system| bits | system.bit()))
+    }
+}
+
+impl RomanizationSystems {
+    pub fn contains(&self, system: RomanizationSystem) -> bool {
+        self.0 & system.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=RomanizationSystem> + '_ {
+        RomanizationSystem::iter().filter(move |&system| self.contains(system))
+    }
+
+    pub fn toggle(&mut self, system: RomanizationSystem) {
+        self.0 ^= system.bit();
+    }
+}
+
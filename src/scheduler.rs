@@ -0,0 +1,338 @@
+//! Strategies for choosing which kana the game presents next.
+//!
+//! A Scheduler is handed the list of translations currently in play and picks one
+//! of them each time the KanaLine needs new content. The result of every answer is
+//! fed back through `Scheduler::record`, which allows a strategy to steer the drill
+//! towards the kana that the player keeps missing.
+//!
+//! Randomness is drawn from the generator handed to `Scheduler::next`, so that the order
+//! of a round is reproducible from the seed of that generator.
+//!
+//! "Time" for the spaced repetition strategy is measured in presentations rather than
+//! seconds, as the KanaLine generates its contents well ahead of the player.
+use std::collections::{HashMap, VecDeque};
+
+use rand::{seq::SliceRandom, Rng, RngCore};
+use strum::{Display, EnumIter};
+
+use crate::{
+    confusables::{confusable_pairs, ConfusionMatrix},
+    kana::Kana,
+    translations::KanaTranslation,
+};
+
+/// The initial ease factor of an item, as defined by SM-2.
+const INITIAL_EASE: f32 = 2.5;
+/// The lower bound of the ease factor, as defined by SM-2.
+const MINIMUM_EASE: f32 = 1.3;
+/// The number of presentations until an item is due after its first correct answer.
+const INITIAL_INTERVAL: u32 = 4;
+/// The sampling weight of an item that has never been answered.
+const NEW_WEIGHT: f32 = 1.0;
+/// The sampling weight of an item that has been answered but is not yet due.
+const LEARNED_WEIGHT: f32 = 0.1;
+/// The additional sampling weight of an item per recorded lapse.
+const LAPSE_WEIGHT: f32 = 0.5;
+/// The sampling weight of a pair of kana from the curated table of confusables.
+const CURATED_PAIR_WEIGHT: f32 = 1.0;
+/// The additional sampling weight of a pair of kana per time one was mistaken for the other.
+const MISTAKEN_PAIR_WEIGHT: f32 = 2.0;
+/// The number of times each kana of a pair is presented in a run.
+const PAIR_REPETITIONS: usize = 2;
+
+
+pub trait Scheduler {
+
+    /// Choose the next translation to present from those currently in play.
+    fn next(
+        &mut self,
+        translations: &[&'static KanaTranslation],
+        rng: &mut dyn RngCore,
+    ) -> Option<&'static KanaTranslation>;
+
+    /// Record the result of answering the given translation.
+    fn record(&mut self, _translation: &'static KanaTranslation, _is_correct: bool) {}
+
+    /// Record that a kana was mistaken for another kana, i.e. answering "tsu" for シ.
+    fn record_confusion(&mut self, _kana: Kana, _mistaken_for: Kana) {}
+}
+
+
+/// Enumeration of the available scheduling strategies.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Display, EnumIter)]
+pub enum SchedulerStrategy {
+    Uniform,
+    #[default]
+    #[strum(serialize = "Spaced Repetition")]
+    SpacedRepetition,
+    #[strum(serialize = "Confusables")]
+    Confusables,
+}
+
+impl SchedulerStrategy {
+    pub fn build(&self) -> Box<dyn Scheduler> {
+        match self {
+            Self::Uniform => Box::new(UniformScheduler),
+            Self::SpacedRepetition => Box::new(SpacedRepetitionScheduler::default()),
+            Self::Confusables => Box::new(ConfusablesScheduler::default()),
+        }
+    }
+}
+
+
+/// Picks every translation with equal probability.
+pub struct UniformScheduler;
+
+impl Scheduler for UniformScheduler {
+    fn next(
+        &mut self,
+        translations: &[&'static KanaTranslation],
+        rng: &mut dyn RngCore,
+    ) -> Option<&'static KanaTranslation> {
+        if translations.is_empty() {
+            return None
+        }
+        Some(translations[rng.gen_range(0..translations.len())])
+    }
+}
+
+
+/// The SM-2 review state of a single translation.
+#[derive(Clone, Debug)]
+pub struct ReviewState {
+    pub ease: f32,
+    pub interval: u32,
+    pub lapses: u32,
+    pub repetitions: u32,
+    pub due: u64,
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        Self { ease: INITIAL_EASE, interval: 0, lapses: 0, repetitions: 0, due: 0 }
+    }
+}
+
+impl ReviewState {
+
+    /// Update the review state following the SM-2 algorithm, where a correct
+    ///   answer is treated as a "good" response and a mistake as a "fail".
+    fn review(&mut self, is_correct: bool, now: u64) {
+        if is_correct {
+            self.repetitions += 1;
+            self.interval = match self.repetitions {
+                1 => INITIAL_INTERVAL,
+                _ => (self.interval as f32 * self.ease).round() as u32,
+            };
+            self.ease += 0.1;
+        } else {
+            self.lapses += 1;
+            self.repetitions = 0;
+            self.interval = 1;
+            self.ease = (self.ease - 0.2).max(MINIMUM_EASE);
+        }
+        self.due = now + self.interval as u64;
+    }
+
+    /// The relative likelihood of presenting this item at the given time.
+    fn weight(&self, now: u64) -> f32 {
+        let lapses = self.lapses as f32 * LAPSE_WEIGHT;
+        if now >= self.due {
+            let overdue = (now - self.due) as f32 / self.interval.max(1) as f32;
+            NEW_WEIGHT + overdue + lapses
+        } else {
+            LEARNED_WEIGHT + lapses
+        }
+    }
+}
+
+
+/// Weights the selection of translations towards those which are due for review
+///   or which have been answered incorrectly.
+#[derive(Default)]
+pub struct SpacedRepetitionScheduler {
+    clock: u64,
+    states: HashMap<Kana, ReviewState>,
+}
+
+impl SpacedRepetitionScheduler {
+    pub fn state(&self, kana: Kana) -> Option<&ReviewState> {
+        self.states.get(&kana)
+    }
+
+    fn weight(&self, translation: &KanaTranslation) -> f32 {
+        self.states.get(&translation.kana)
+            .map_or(NEW_WEIGHT, |state| state.weight(self.clock))
+    }
+}
+
+impl Scheduler for SpacedRepetitionScheduler {
+    fn next(
+        &mut self,
+        translations: &[&'static KanaTranslation],
+        rng: &mut dyn RngCore,
+    ) -> Option<&'static KanaTranslation> {
+        let weights: Vec<f32> = translations.iter().map(|t| self.weight(t)).collect();
+        let total: f32 = weights.iter().sum();
+        if translations.is_empty() || total <= 0.0 {
+            return None
+        }
+        self.clock += 1;
+
+        let mut target = rng.gen::<f32>() * total;
+        for (translation, weight) in translations.iter().zip(weights) {
+            if target < weight {
+                return Some(translation)
+            }
+            target -= weight;
+        }
+        translations.last().copied()
+    }
+
+    fn record(&mut self, translation: &'static KanaTranslation, is_correct: bool) {
+        let now = self.clock;
+        self.states.entry(translation.kana)
+            .or_default()
+            .review(is_correct, now);
+    }
+}
+
+
+/// Interleaves pairs of kana which are easily mistaken for one another, so that the player
+///   practices telling them apart, i.e. シ ツ ツ シ.
+///
+/// Pairs are drawn from the curated table of confusables, weighted towards those which
+///   the player has mistaken before. Should no pair be in play, then spaced repetition is used instead.
+pub struct ConfusablesScheduler {
+    fallback: SpacedRepetitionScheduler,
+    pairs: Vec<((Kana, Kana), f32)>,
+    /// The remaining kana of the current run of a pair.
+    run: VecDeque<&'static KanaTranslation>,
+}
+
+impl Default for ConfusablesScheduler {
+    /// A scheduler which only knows of the curated table of confusables.
+    fn default() -> Self {
+        Self::new(&ConfusionMatrix::new())
+    }
+}
+
+impl ConfusablesScheduler {
+
+    /// A scheduler which also knows of the player's own mistakes.
+    pub fn new(matrix: &ConfusionMatrix) -> Self {
+        let mut scheduler = Self {
+            fallback: SpacedRepetitionScheduler::default(),
+            pairs: Vec::new(),
+            run: VecDeque::new(),
+        };
+        for pair in confusable_pairs() {
+            scheduler.add_weight(pair, CURATED_PAIR_WEIGHT);
+        }
+        for (&pair, &count) in matrix {
+            scheduler.add_weight(pair, count as f32 * MISTAKEN_PAIR_WEIGHT);
+        }
+        scheduler
+    }
+
+    /// Pairs are unordered, as telling シ from ツ is also telling ツ from シ.
+    fn add_weight(&mut self, (a, b): (Kana, Kana), weight: f32) {
+        let pair = (a.min(b), a.max(b));
+        match self.pairs.iter_mut().find(|(p, _)| *p == pair) {
+            Some((_, total)) => *total += weight,
+            None => self.pairs.push((pair, weight)),
+        }
+    }
+
+    /// Start a run of a pair of kana which are both in play, shuffled so that neither
+    ///   kana can be answered from the order alone.
+    fn start_run(&mut self, translations: &[&'static KanaTranslation], rng: &mut dyn RngCore) -> bool {
+        let in_play = |kana: Kana| translations.iter().copied().find(|t| t.kana == kana);
+        let candidates: Vec<_> = self.pairs.iter()
+            .filter_map(|&((a, b), weight)| Some(((in_play(a)?, in_play(b)?), weight)))
+            .collect();
+        let ((a, b), _) = match candidates.choose_weighted(&mut *rng, |(_, weight)| *weight) {
+            Ok(&pair) => pair,
+            Err(_) => return false,
+        };
+        let mut run: Vec<_> = std::iter::repeat([a, b]).take(PAIR_REPETITIONS).flatten().collect();
+        run.shuffle(rng);
+        self.run = run.into();
+        true
+    }
+}
+
+impl Scheduler for ConfusablesScheduler {
+    fn next(
+        &mut self,
+        translations: &[&'static KanaTranslation],
+        rng: &mut dyn RngCore,
+    ) -> Option<&'static KanaTranslation> {
+        // The run is abandoned should the kana in play change part way through it.
+        self.run.retain(|translation| translations.iter().any(|t| t.kana == translation.kana));
+        if self.run.is_empty() && !self.start_run(translations, rng) {
+            return self.fallback.next(translations, rng)
+        }
+        self.run.pop_front()
+    }
+
+    fn record(&mut self, translation: &'static KanaTranslation, is_correct: bool) {
+        self.fallback.record(translation, is_correct);
+    }
+
+    /// A mistake makes the pair of kana more likely to come up again this session.
+    fn record_confusion(&mut self, kana: Kana, mistaken_for: Kana) {
+        self.add_weight((kana, mistaken_for), MISTAKEN_PAIR_WEIGHT);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn translations() -> Vec<&'static KanaTranslation> {
+        Kana::iter().filter(|kana| kana.chart().is_some()).take(10).map(Kana::translation).collect()
+    }
+
+    /// The kana presented by a spaced repetition scheduler, where every third answer is a mistake.
+    fn drill(seed: u64) -> Vec<&'static str> {
+        let translations = translations();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut scheduler = SpacedRepetitionScheduler::default();
+        (0..50)
+            .map(|index| {
+                let translation = scheduler.next(&translations, &mut rng).expect("no translation was chosen");
+                scheduler.record(translation, index % 3 != 0);
+                translation.symbol
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spaced_repetition_is_reproducible_from_the_seed() {
+        assert_eq!(drill(7), drill(7));
+        assert_ne!(drill(7), drill(8));
+    }
+
+    #[test]
+    fn spaced_repetition_favours_mistakes() {
+        let translations = translations();
+        let mut scheduler = SpacedRepetitionScheduler::default();
+        scheduler.record(translations[0], false);
+        scheduler.record(translations[1], true);
+        // The mistake is due again after a single presentation, whereas the correct answer is not.
+        scheduler.clock = 2;
+        assert!(scheduler.weight(translations[0]) > scheduler.weight(translations[2]));
+        assert!(scheduler.weight(translations[1]) < scheduler.weight(translations[2]));
+    }
+
+    #[test]
+    fn nothing_is_chosen_from_nothing() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert!(UniformScheduler.next(&[], &mut rng).is_none());
+        assert!(SpacedRepetitionScheduler::default().next(&[], &mut rng).is_none());
+    }
+}
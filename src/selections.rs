@@ -0,0 +1,160 @@
+//! The kana selections which can be switched between on the KanaSelector.
+//!
+//! The built-in Presets are derived from the kana data, while the named custom sets of
+//! the player are kept by the SelectionStore within the browser's localStorage, along with
+//! the last selection made so that it is restored when the page is next loaded. The kana
+//! are stored by their symbols, so that changes to the kana data do not invalidate them.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+use yew::format::{Json, Text};
+use yew::services::ConsoleService;
+use yew::services::storage::{Area, StorageService};
+
+use crate::{
+    charts::ChartVersion,
+    confusables::look_alikes,
+    kana::{Kana, SyllabaryVersion},
+};
+
+/// The localStorage key under which the selections are stored.
+const STORAGE_KEY: &str = "kana-kilo.selections";
+
+
+/// Enumeration of the built-in selections.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Display, EnumIter)]
+pub enum Preset {
+    #[strum(serialize = "Basic Hiragana")]
+    BasicHiragana,
+    #[strum(serialize = "All Hiragana")]
+    AllHiragana,
+    #[strum(serialize = "All Katakana")]
+    AllKatakana,
+    #[strum(serialize = "Katakana Dakuon")]
+    KatakanaDakuon,
+    #[strum(serialize = "Yōon Only")]
+    Yoon,
+    #[strum(serialize = "Look-Alikes")]
+    LookAlikes,
+}
+
+impl Preset {
+
+    /// The kana of the preset, which are limited to those within the charts.
+    pub fn kana(self) -> Vec<Kana> {
+        let charted = Kana::iter().filter(|kana| kana.chart().is_some());
+        match self {
+            Self::BasicHiragana => charted
+                .filter(|kana| kana.syllabary() == SyllabaryVersion::Hirigana)
+                .filter(|kana| kana.chart() == Some(ChartVersion::Basic))
+                .collect(),
+            Self::AllHiragana => charted
+                .filter(|kana| kana.syllabary() == SyllabaryVersion::Hirigana)
+                .collect(),
+            Self::AllKatakana => charted
+                .filter(|kana| kana.syllabary() == SyllabaryVersion::Katakana)
+                .collect(),
+            Self::KatakanaDakuon => charted
+                .filter(|kana| kana.syllabary() == SyllabaryVersion::Katakana)
+                .filter(|kana| kana.chart() == Some(ChartVersion::Dakuon))
+                .collect(),
+            Self::Yoon => charted
+                .filter(|kana| kana.yoon().is_some())
+                .collect(),
+            Self::LookAlikes => {
+                let look_alikes: Vec<Kana> = look_alikes().collect();
+                charted.filter(|kana| look_alikes.contains(kana)).collect()
+            },
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct SelectionStore {
+    /// The custom sets of the player by their names.
+    sets: BTreeMap<String, Vec<String>>,
+    /// The most recent selection, if any has been made.
+    last: Option<Vec<String>>,
+}
+
+impl SelectionStore {
+
+    /// Load the selections from localStorage, falling back to an empty store if
+    ///   there are none or they could not be read.
+    pub fn load() -> Self {
+        let storage = match StorageService::new(Area::Local) {
+            Ok(storage) => storage,
+            Err(error) => {
+                ConsoleService::warn(error);
+                return Self::default()
+            }
+        };
+        let text: Text = storage.restore(STORAGE_KEY);
+        match text {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|error| {
+                ConsoleService::error(&format!("Could not load selections: {}", error));
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write the selections to localStorage.
+    pub fn save(&self) {
+        match StorageService::new(Area::Local) {
+            Ok(mut storage) => storage.store(STORAGE_KEY, Json(self)),
+            Err(error) => ConsoleService::warn(error),
+        }
+    }
+
+    /// The names of the custom sets, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.sets.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Vec<Kana>> {
+        self.sets.get(name).map(|symbols| to_kana(symbols))
+    }
+
+    /// Save the kana as the custom set of the given name, replacing any set of that name.
+    pub fn insert(&mut self, name: &str, kana: &[Kana]) {
+        self.sets.insert(name.into(), to_symbols(kana));
+    }
+
+    /// Rename a custom set, returning whether it was renamed.
+    /// A set is not renamed over another existing set.
+    pub fn rename(&mut self, from: &str, to: &str) -> bool {
+        if from == to || self.sets.contains_key(to) {
+            return false
+        }
+        match self.sets.remove(from) {
+            Some(symbols) => {
+                self.sets.insert(to.into(), symbols);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.sets.remove(name);
+    }
+
+    /// The most recent selection, if any has been made.
+    pub fn last(&self) -> Option<Vec<Kana>> {
+        self.last.as_deref().map(to_kana)
+    }
+
+    pub fn set_last(&mut self, kana: &[Kana]) {
+        self.last = Some(to_symbols(kana));
+    }
+}
+
+fn to_kana(symbols: &[String]) -> Vec<Kana> {
+    symbols.iter().filter_map(|symbol| Kana::from_symbol(symbol)).collect()
+}
+
+fn to_symbols(kana: &[Kana]) -> Vec<String> {
+    kana.iter().map(|kana| kana.symbol().to_string()).collect()
+}
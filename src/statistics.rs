@@ -0,0 +1,377 @@
+//! Persistent per-kana statistics.
+//!
+//! The StatisticsStore records the history of every answer keyed by the symbol of its Kana,
+//! so that changes to the kana data do not invalidate it, and persists it within the
+//! browser's localStorage. The stored JSON is wrapped in a
+//! versioned envelope, and older versions are brought up to date by the functions in
+//! MIGRATIONS before being deserialized.
+//!
+//! Components share a single store through a StatisticsHandle, which is created by
+//! the App and passed down through the component properties.
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use yew::format::{Json, Text};
+use yew::services::ConsoleService;
+use yew::services::storage::{Area, StorageService};
+
+use crate::{
+    confusables::{mistaken_for, ConfusionMatrix},
+    grading::Grade,
+    kana::Kana,
+    translations::KanaTranslation,
+};
+
+/// The localStorage key under which the statistics are stored.
+const STORAGE_KEY: &str = "kana-kilo.statistics";
+/// The current version of the serialization format.
+const FORMAT_VERSION: u64 = 4;
+/// The number of most recent response times retained per kana.
+const RESPONSE_TIME_LIMIT: usize = 25;
+/// The number of most recent sessions retained per kana.
+const SESSION_LIMIT: usize = 10;
+
+/// Migrations between versions of the serialization format.
+/// The function at index `i` migrates from version `i + 1` to version `i + 2`.
+const MIGRATIONS: &[fn(&mut Value)] = &[
+    migrate_v1_sessions,
+    migrate_v2_confusions,
+    migrate_v3_near_misses,
+];
+
+/// Version 2 introduced the per-session history of each kana.
+fn migrate_v1_sessions(value: &mut Value) {
+    if let Some(kana) = value.get_mut("kana").and_then(Value::as_object_mut) {
+        for statistics in kana.values_mut().filter_map(Value::as_object_mut) {
+            statistics.insert("sessions".into(), Value::Array(Vec::new()));
+        }
+    }
+}
+
+/// Version 3 introduced the kana which each kana has been mistaken for,
+///   which are recovered from the incorrect answers recorded until then.
+fn migrate_v2_confusions(value: &mut Value) {
+    if let Some(kana) = value.get_mut("kana").and_then(Value::as_object_mut) {
+        for (symbol, statistics) in kana.iter_mut() {
+            let statistics = match statistics.as_object_mut() {
+                Some(statistics) => statistics,
+                None => continue,
+            };
+            let mut confusions = serde_json::Map::new();
+            let wrong_answers = statistics.get("wrong_answers").and_then(Value::as_object);
+            for (text, count) in wrong_answers.into_iter().flatten() {
+                let other = Kana::from_symbol(symbol).and_then(|kana| mistaken_for(kana, text));
+                if let (Some(other), Some(count)) = (other, count.as_u64()) {
+                    let total = confusions.get(other.symbol()).and_then(Value::as_u64).unwrap_or(0);
+                    confusions.insert(other.symbol().into(), (total + count).into());
+                }
+            }
+            statistics.insert("confusions".into(), Value::Object(confusions));
+        }
+    }
+}
+
+/// Version 4 introduced near-misses, which were previously recorded as incorrect answers.
+fn migrate_v3_near_misses(value: &mut Value) {
+    if let Some(kana) = value.get_mut("kana").and_then(Value::as_object_mut) {
+        for statistics in kana.values_mut().filter_map(Value::as_object_mut) {
+            statistics.insert("near_misses".into(), 0.into());
+            let sessions = statistics.get_mut("sessions").and_then(Value::as_array_mut);
+            for session in sessions.into_iter().flatten().filter_map(Value::as_object_mut) {
+                session.insert("near_misses".into(), 0.into());
+            }
+        }
+    }
+}
+
+
+/// The recorded history of a single kana.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct KanaStatistics {
+    pub attempts: u32,
+    pub correct: u32,
+    pub near_misses: u32,
+    /// Milliseconds since the UNIX epoch at which the kana was last answered.
+    pub last_seen: f64,
+    /// The most recent response times in milliseconds, oldest first.
+    pub response_times: Vec<f64>,
+    /// The number of times each incorrect romanji was typed.
+    pub wrong_answers: BTreeMap<String, u32>,
+    /// The number of times the answer of each other kana was given for this kana.
+    pub confusions: BTreeMap<String, u32>,
+    /// The results of the most recent sessions, oldest first.
+    pub sessions: Vec<SessionStatistics>,
+}
+
+/// The results of a kana within a single game session.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SessionStatistics {
+    /// Milliseconds since the UNIX epoch at which the session started.
+    pub started: f64,
+    pub attempts: u32,
+    pub correct: u32,
+    pub near_misses: u32,
+}
+
+impl SessionStatistics {
+    pub fn accuracy(&self) -> f32 {
+        weighted_accuracy(self.correct, self.near_misses, self.attempts.max(1))
+    }
+}
+
+impl KanaStatistics {
+
+    pub fn accuracy(&self) -> Option<f32> {
+        match self.attempts {
+            0 => None,
+            attempts => Some(weighted_accuracy(self.correct, self.near_misses, attempts)),
+        }
+    }
+
+    pub fn median_response_time(&self) -> Option<f64> {
+        if self.response_times.is_empty() {
+            return None
+        }
+        let mut times = self.response_times.clone();
+        times.sort_by(|a, b| a.total_cmp(b));
+        let middle = times.len() / 2;
+        match times.len() % 2 {
+            0 => Some((times[middle - 1] + times[middle]) / 2.0),
+            _ => Some(times[middle]),
+        }
+    }
+
+    /// The incorrect answers ordered from most to least frequent.
+    pub fn common_wrong_answers(&self) -> Vec<(&str, u32)> {
+        let mut answers: Vec<(&str, u32)> = self.wrong_answers.iter()
+            .map(|(answer, count)| (answer.as_str(), *count))
+            .collect();
+        answers.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        answers
+    }
+
+    /// The kana which this kana has been mistaken for, ordered from most to least frequent.
+    pub fn common_confusions(&self) -> Vec<(&str, u32)> {
+        let mut confusions: Vec<(&str, u32)> = self.confusions.iter()
+            .map(|(kana, count)| (kana.as_str(), *count))
+            .collect();
+        confusions.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        confusions
+    }
+
+    fn record(&mut self, kana: Kana, text: &str, grade: Grade, response_time: f64, session: f64, now: f64) {
+        self.attempts += 1;
+        self.last_seen = now;
+        match grade {
+            Grade::Correct => self.correct += 1,
+            Grade::NearMiss => self.near_misses += 1,
+            // Handwritten answers have no text to record.
+            Grade::Wrong if text.is_empty() => {},
+            Grade::Wrong => {
+                *self.wrong_answers.entry(text.into()).or_default() += 1;
+                if let Some(other) = mistaken_for(kana, text) {
+                    *self.confusions.entry(other.symbol().into()).or_default() += 1;
+                }
+            },
+        }
+        self.response_times.push(response_time);
+        if self.response_times.len() > RESPONSE_TIME_LIMIT {
+            self.response_times.remove(0);
+        }
+
+        if self.sessions.last().map_or(true, |last| last.started != session) {
+            self.sessions.push(SessionStatistics { started: session, ..Default::default() });
+            if self.sessions.len() > SESSION_LIMIT {
+                self.sessions.remove(0);
+            }
+        }
+        let current = self.sessions.last_mut().unwrap();
+        current.attempts += 1;
+        match grade {
+            Grade::Correct => current.correct += 1,
+            Grade::NearMiss => current.near_misses += 1,
+            Grade::Wrong => {},
+        }
+    }
+}
+
+/// The accuracy of the attempts, where near-misses are given partial credit.
+fn weighted_accuracy(correct: u32, near_misses: u32, attempts: u32) -> f32 {
+    let points = correct as f32 * Grade::Correct.weight() + near_misses as f32 * Grade::NearMiss.weight();
+    points / attempts as f32
+}
+
+
+/// An answer given within the current session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionAnswer {
+    pub translation: &'static KanaTranslation,
+    pub text: String,
+    pub grade: Grade,
+    pub response_time: f64,
+    /// Milliseconds since the UNIX epoch at which the answer was given.
+    pub answered: f64,
+}
+
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct StatisticsStore {
+    kana: BTreeMap<String, KanaStatistics>,
+    /// Milliseconds since the UNIX epoch at which the current session started.
+    #[serde(skip)]
+    session: f64,
+    /// The answers given within the current session, which are not persisted.
+    #[serde(skip)]
+    session_answers: Vec<SessionAnswer>,
+}
+
+/// The versioned envelope that the StatisticsStore is serialized within.
+#[derive(Serialize)]
+struct VersionedStatistics<'a> {
+    version: u64,
+    #[serde(flatten)]
+    store: &'a StatisticsStore,
+}
+
+impl StatisticsStore {
+
+    /// Load the statistics from localStorage, falling back to an empty store if
+    ///   there are none or they could not be read.
+    pub fn load() -> Self {
+        let storage = match StorageService::new(Area::Local) {
+            Ok(storage) => storage,
+            Err(error) => {
+                ConsoleService::warn(error);
+                return Self::default()
+            }
+        };
+        let text: Text = storage.restore(STORAGE_KEY);
+        match text {
+            Ok(text) => Self::deserialize(&text).unwrap_or_else(|error| {
+                ConsoleService::error(&format!("Could not load statistics: {}", error));
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write the statistics to localStorage.
+    pub fn save(&self) {
+        match StorageService::new(Area::Local) {
+            Ok(mut storage) => {
+                let versioned = VersionedStatistics { version: FORMAT_VERSION, store: self };
+                storage.store(STORAGE_KEY, Json(&versioned));
+            },
+            Err(error) => ConsoleService::warn(error),
+        }
+    }
+
+    pub fn get(&self, kana: Kana) -> Option<&KanaStatistics> {
+        self.kana.get(kana.symbol())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&str, &KanaStatistics)> {
+        self.kana.iter().map(|(kana, statistics)| (kana.as_str(), statistics))
+    }
+
+    /// Record an answer of the given translation, where `text` is what the player typed
+    ///   and `response_time` is the number of milliseconds they took to answer.
+    /// The answer of a syllabogram joined with a sokuon or chōonpu (i.e. "って")
+    ///   is attributed to the kana it is joined to, as only the kana of the charts are tracked.
+    pub fn record(&mut self, translation: &'static KanaTranslation, text: &str, grade: Grade, response_time: f64) {
+        let session = self.session;
+        let now = js_sys::Date::now();
+        self.kana.entry(translation.kana.symbol().into())
+            .or_default()
+            .record(translation.kana, text, grade, response_time, session, now);
+        self.session_answers.push(SessionAnswer {
+            translation,
+            text: text.into(),
+            grade,
+            response_time,
+            answered: now,
+        });
+    }
+
+    /// Mark the start of a new game session. Subsequent answers are attributed to it.
+    pub fn start_session(&mut self) {
+        self.session = js_sys::Date::now();
+        self.session_answers.clear();
+    }
+
+    /// Forget the answers of the current session, once they no longer need reviewing.
+    pub fn end_session(&mut self) {
+        self.session_answers.clear();
+    }
+
+    /// Milliseconds since the UNIX epoch at which the current session started.
+    pub fn session_started(&self) -> f64 {
+        self.session
+    }
+
+    pub fn session_answers(&self) -> &[SessionAnswer] {
+        &self.session_answers
+    }
+
+    /// The number of times each kana has been mistaken for another kana.
+    pub fn confusion_matrix(&self) -> ConfusionMatrix {
+        let mut matrix = ConfusionMatrix::new();
+        for (kana, statistics) in &self.kana {
+            let kana = match Kana::from_symbol(kana) {
+                Some(kana) => kana,
+                None => continue,
+            };
+            for (other, &count) in &statistics.confusions {
+                if let Some(other) = Kana::from_symbol(other) {
+                    matrix.insert((kana, other), count);
+                }
+            }
+        }
+        matrix
+    }
+
+    fn deserialize(text: &str) -> Result<Self, String> {
+        let mut value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let version = value.get("version")
+            .and_then(Value::as_u64)
+            .ok_or("missing format version")?;
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(format!("unsupported format version: {}", version))
+        }
+        for migration in &MIGRATIONS[(version - 1) as usize..] {
+            migration(&mut value);
+        }
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+}
+
+
+/// A shared reference to the StatisticsStore.
+///
+/// Equality is determined by identity so that components are not re-rendered
+///   every time a new answer is recorded.
+#[derive(Clone)]
+pub struct StatisticsHandle(Rc<RefCell<StatisticsStore>>);
+
+impl StatisticsHandle {
+    pub fn load() -> Self {
+        Self(Rc::new(RefCell::new(StatisticsStore::load())))
+    }
+
+    pub fn borrow(&self) -> Ref<'_, StatisticsStore> {
+        self.0.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, StatisticsStore> {
+        self.0.borrow_mut()
+    }
+}
+
+impl PartialEq for StatisticsHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -0,0 +1,336 @@
+//! The stroke order of kana, and the grading of handwritten strokes against it.
+//!
+//! Strokes are bundled as KanjiVG formatted SVG path data, drawn within a 109 × 109 box.
+//! Both the reference and the handwritten strokes are reduced to polylines of evenly
+//! spaced points, normalized to the bounding box of the whole character. This makes the
+//! grading indifferent to where, and how large, the player draws the kana.
+use std::fmt;
+
+use crate::kana::Kana;
+
+include!("strokes_codegen.rs");
+
+/// The width and height of the box within which the reference strokes are drawn.
+pub const VIEW_BOX: f64 = 109.0;
+/// The number of evenly spaced points each stroke is resampled to.
+const SAMPLES: usize = 32;
+/// The number of points each Bézier curve segment is flattened to.
+const CURVE_SAMPLES: usize = 12;
+/// The mean distance between the points of a handwritten and reference stroke,
+///   as a fraction of the size of the character, above which the stroke is misshapen.
+const SHAPE_TOLERANCE: f64 = 0.2;
+
+pub type Point = (f64, f64);
+pub type Stroke = Vec<Point>;
+
+
+/// The SVG path data of each stroke of the kana, in the order they are written.
+pub fn stroke_paths(kana: Kana) -> Option<&'static [&'static str]> {
+    STROKES.get(kana.symbol()).copied()
+}
+
+/// Every kana which has stroke data, and so can be drawn within the Writing mode.
+pub fn drawable_kana() -> impl Iterator<Item=Kana> {
+    Kana::iter().filter(|&kana| stroke_paths(kana).is_some())
+}
+
+/// The strokes of the kana as polylines within the VIEW_BOX.
+pub fn reference_strokes(kana: Kana) -> Option<Vec<Stroke>> {
+    stroke_paths(kana).map(|paths| paths.iter().map(|d| parse_path(d)).collect())
+}
+
+/// A mistake made in writing a kana, where strokes are numbered from one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StrokeFault {
+    Count { expected: usize, drawn: usize },
+    Order(usize),
+    Direction(usize),
+    Shape(usize),
+}
+
+impl fmt::Display for StrokeFault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Count { expected, drawn } => write!(f, "{} of {} strokes", drawn, expected),
+            Self::Order(stroke) => write!(f, "stroke {} out of order", stroke),
+            Self::Direction(stroke) => write!(f, "stroke {} backwards", stroke),
+            Self::Shape(stroke) => write!(f, "stroke {} misshapen", stroke),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokeGrade {
+    pub faults: Vec<StrokeFault>,
+    /// How closely the strokes match the reference, from zero to one.
+    pub similarity: f64,
+}
+
+impl StrokeGrade {
+    pub fn is_correct(&self) -> bool {
+        self.faults.is_empty()
+    }
+}
+
+/// Grade the handwritten strokes against the reference strokes for their count,
+///   order, direction and shape.
+pub fn grade(reference: &[Stroke], drawn: &[Stroke]) -> StrokeGrade {
+    let reference = normalize(reference);
+    let drawn = normalize(drawn);
+    let mut faults = Vec::new();
+    if drawn.len() != reference.len() {
+        faults.push(StrokeFault::Count { expected: reference.len(), drawn: drawn.len() });
+    }
+
+    let mut distances = Vec::with_capacity(drawn.len());
+    for (index, stroke) in drawn.iter().enumerate() {
+        let reversed: Stroke = stroke.iter().rev().copied().collect();
+        // The reference stroke which the handwritten stroke most resembles, in either direction.
+        let closest = reference.iter()
+            .map(|r| mean_distance(stroke, r).min(mean_distance(&reversed, r)))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let expected = match reference.get(index) {
+            Some(expected) => expected,
+            None => continue,
+        };
+        let forwards = mean_distance(stroke, expected);
+        let backwards = mean_distance(&reversed, expected);
+        distances.push(forwards.min(backwards));
+
+        if closest.is_some_and(|(closest, _)| closest != index) {
+            faults.push(StrokeFault::Order(index + 1));
+        } else if backwards < forwards {
+            faults.push(StrokeFault::Direction(index + 1));
+        } else if forwards > SHAPE_TOLERANCE {
+            faults.push(StrokeFault::Shape(index + 1));
+        }
+    }
+
+    let similarity = match distances.is_empty() {
+        true => 0.0,
+        false => {
+            let mean = distances.iter().sum::<f64>() / distances.len() as f64;
+            let matched = distances.len() as f64 / reference.len().max(drawn.len()) as f64;
+            (1.0 - mean / (2.0 * SHAPE_TOLERANCE)).clamp(0.0, 1.0) * matched
+        },
+    };
+    StrokeGrade { faults, similarity }
+}
+
+/// Resample each stroke and scale the character to fit a unit box, keeping its aspect ratio.
+fn normalize(strokes: &[Stroke]) -> Vec<Stroke> {
+    let points = strokes.iter().flatten();
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in points {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let size = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+    strokes.iter()
+        .filter(|stroke| !stroke.is_empty())
+        .map(|stroke| {
+            resample(stroke).into_iter()
+                .map(|(x, y)| ((x - min_x) / size, (y - min_y) / size))
+                .collect()
+        })
+        .collect()
+}
+
+/// The polyline resampled to SAMPLES points evenly spaced along its length.
+fn resample(stroke: &[Point]) -> Stroke {
+    let length: f64 = stroke.windows(2).map(|w| distance(w[0], w[1])).sum();
+    if length <= f64::EPSILON {
+        return vec![stroke[0]; SAMPLES]
+    }
+    let step = length / (SAMPLES - 1) as f64;
+    let mut resampled = Vec::with_capacity(SAMPLES);
+    resampled.push(stroke[0]);
+    let mut travelled = 0.0;
+    let mut target = step;
+    for segment in stroke.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let span = distance(from, to);
+        while span > 0.0 && travelled + span >= target && resampled.len() < SAMPLES - 1 {
+            let t = (target - travelled) / span;
+            resampled.push((from.0 + t * (to.0 - from.0), from.1 + t * (to.1 - from.1)));
+            target += step;
+        }
+        travelled += span;
+    }
+    resampled.resize(SAMPLES, stroke[stroke.len() - 1]);
+    resampled
+}
+
+fn mean_distance(a: &[Point], b: &[Point]) -> f64 {
+    a.iter().zip(b).map(|(&p, &q)| distance(p, q)).sum::<f64>() / a.len().max(1) as f64
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// Flatten SVG path data into a polyline.
+/// The move, line and Bézier curve commands used by KanjiVG are supported, in both their
+///   absolute and relative forms.
+pub fn parse_path(d: &str) -> Stroke {
+    let tokens = tokenize(d);
+    let mut points = Vec::new();
+    let mut current: Point = (0.0, 0.0);
+    let mut start: Point = (0.0, 0.0);
+    // The second control point of the previous curve, reflected by the smooth curve commands.
+    let mut control: Option<Point> = None;
+    let mut command = 'M';
+    let mut index = 0;
+
+    while index < tokens.len() {
+        if let PathToken::Command(c) = tokens[index] {
+            command = c;
+            index += 1;
+            if c.eq_ignore_ascii_case(&'z') {
+                points.push(start);
+                current = start;
+                control = None;
+                continue
+            }
+        }
+        let arity = match command.to_ascii_uppercase() {
+            'H' | 'V' => 1,
+            'M' | 'L' | 'T' => 2,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            _ => return points,
+        };
+        let numbers: Vec<f64> = match tokens.get(index..index + arity) {
+            Some(numbers) => numbers.iter()
+                .map_while(|token| match token {
+                    PathToken::Number(n) => Some(*n),
+                    PathToken::Command(_) => None,
+                })
+                .collect(),
+            None => return points,
+        };
+        if numbers.len() != arity {
+            return points
+        }
+        index += arity;
+
+        let relative = command.is_ascii_lowercase();
+        let point = |x: f64, y: f64| match relative {
+            true => (current.0 + x, current.1 + y),
+            false => (x, y),
+        };
+        let reflected = control.map_or(current, |(x, y)| (2.0 * current.0 - x, 2.0 * current.1 - y));
+        match command.to_ascii_uppercase() {
+            'M' => {
+                current = point(numbers[0], numbers[1]);
+                start = current;
+                points.push(current);
+                // Subsequent coordinate pairs are implicit line commands.
+                command = if relative { 'l' } else { 'L' };
+                control = None;
+            },
+            'L' | 'T' => {
+                current = point(numbers[0], numbers[1]);
+                points.push(current);
+                control = None;
+            },
+            'H' => {
+                current.0 = if relative { current.0 + numbers[0] } else { numbers[0] };
+                points.push(current);
+                control = None;
+            },
+            'V' => {
+                current.1 = if relative { current.1 + numbers[0] } else { numbers[0] };
+                points.push(current);
+                control = None;
+            },
+            'C' => {
+                let (c1, c2, end) = (
+                    point(numbers[0], numbers[1]),
+                    point(numbers[2], numbers[3]),
+                    point(numbers[4], numbers[5]),
+                );
+                flatten_cubic(&mut points, current, c1, c2, end);
+                control = Some(c2);
+                current = end;
+            },
+            'S' => {
+                let (c2, end) = (point(numbers[0], numbers[1]), point(numbers[2], numbers[3]));
+                flatten_cubic(&mut points, current, reflected, c2, end);
+                control = Some(c2);
+                current = end;
+            },
+            'Q' => {
+                let (c, end) = (point(numbers[0], numbers[1]), point(numbers[2], numbers[3]));
+                // A quadratic curve is the cubic curve with control points two thirds of the way to c.
+                let c1 = (current.0 + 2.0 / 3.0 * (c.0 - current.0), current.1 + 2.0 / 3.0 * (c.1 - current.1));
+                let c2 = (end.0 + 2.0 / 3.0 * (c.0 - end.0), end.1 + 2.0 / 3.0 * (c.1 - end.1));
+                flatten_cubic(&mut points, current, c1, c2, end);
+                control = None;
+                current = end;
+            },
+            _ => return points,
+        }
+    }
+    points
+}
+
+fn flatten_cubic(points: &mut Stroke, p0: Point, p1: Point, p2: Point, p3: Point) {
+    for step in 1..=CURVE_SAMPLES {
+        let t = step as f64 / CURVE_SAMPLES as f64;
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        points.push((
+            a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+            a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+        ));
+    }
+}
+
+enum PathToken {
+    Command(char),
+    Number(f64),
+}
+
+/// Split SVG path data into commands and numbers.
+/// Numbers need not be separated where unambiguous, e.g. "1.5-2.5.5" is 1.5, -2.5 and 0.5.
+fn tokenize(d: &str) -> Vec<PathToken> {
+    let mut tokens = Vec::new();
+    let mut number = String::new();
+    let flush = |number: &mut String, tokens: &mut Vec<PathToken>| {
+        if let Ok(n) = number.parse() {
+            tokens.push(PathToken::Number(n));
+        }
+        number.clear();
+    };
+    for c in d.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            '.' => {
+                if number.contains('.') {
+                    flush(&mut number, &mut tokens);
+                }
+                number.push(c);
+            },
+            '-' | '+' => {
+                // A sign directly after an exponent belongs to it.
+                if !number.ends_with(['e', 'E']) {
+                    flush(&mut number, &mut tokens);
+                }
+                number.push(c);
+            },
+            'e' | 'E' if !number.is_empty() => number.push(c),
+            c if c.is_ascii_alphabetic() => {
+                flush(&mut number, &mut tokens);
+                tokens.push(PathToken::Command(c));
+            },
+            _ => flush(&mut number, &mut tokens),
+        }
+    }
+    flush(&mut number, &mut tokens);
+    tokens
+}
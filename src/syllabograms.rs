@@ -5,6 +5,10 @@
 //!
 //! TODO: Implement the rest of this with proper error handling.
 const SMALL_KANA: &str = "ゃゅょャュョァィゥェォ";
+/// The sokuon, which geminates the consonant of the syllabogram that follows it.
+pub const SOKUON: &str = "っッ";
+/// The chōonpu, which prolongs the vowel of the syllabogram that precedes it.
+pub const CHOONPU: char = 'ー';
 
 
 pub struct Syllabograms<'a> {
@@ -72,11 +76,23 @@ impl SyllabogramCursor {
         if self.offset == self.len {
             return Ok(None)
         }
-        let mut iter = chunk[self.offset - chunk_start..].chars();
-        let ch = iter.next().unwrap();
+        let mut iter = chunk[self.offset - chunk_start..].chars().peekable();
+        let mut ch = iter.next().unwrap();
         self.offset += ch.len_utf8();
-        if let Some(next_ch) = iter.next() {
-            if SMALL_KANA.contains(next_ch) {
+
+        // A sokuon is joined with the syllabogram which follows it.
+        if SOKUON.contains(ch) {
+            if let Some(next_ch) = iter.next_if(|&c| !SOKUON.contains(c) && c != CHOONPU) {
+                ch = next_ch;
+                self.offset += ch.len_utf8();
+            }
+        }
+        if !ch.is_ascii() {
+            if let Some(next_ch) = iter.next_if(|&c| SMALL_KANA.contains(c)) {
+                self.offset += next_ch.len_utf8();
+            }
+            // Any number of chōonpu are joined with the syllabogram which precedes them.
+            while let Some(next_ch) = iter.next_if_eq(&CHOONPU) {
                 self.offset += next_ch.len_utf8();
             }
         }
@@ -1,19 +1,118 @@
 //! This file is where the syllabogram translations are accessed.
 //!
-//! The KANA_TO_ROMANJI map is generated by the build.rs script and stored
-//! within the included kana_to_romanji_codegen.rs file.
-include!("kana_to_romanji_codegen.rs");  // KANA_TO_ROMANJI
+//! The TRANSLATIONS table, which holds the translation of each Kana in order, and the
+//! COMPOUND_TRANSLATIONS map, which holds those of each kana joined with a sokuon (っ)
+//! and/or chōonpu (ー), are generated by the build.rs script and stored within the
+//! included kana_to_romanji_codegen.rs file.
+include!("kana_to_romanji_codegen.rs");  // TRANSLATIONS, COMPOUND_TRANSLATIONS
 
-#[derive(PartialEq)]
+use crate::{
+    audio::audio_clip,
+    kana::Kana,
+    romanization::{RomanizationSystem, RomanizationSystems},
+};
+
+#[derive(Debug, PartialEq)]
 pub struct KanaTranslation {
-    pub kana: &'static str,
+    /// The kana being translated. For a syllabogram joined with a sokuon or chōonpu
+    ///   this is the kana it is joined to, i.e. "き" for "っき".
+    pub kana: Kana,
+    /// The syllabogram as it is written, i.e. "き" or "っき".
+    pub symbol: &'static str,
+    /// The romanji shown to the player, which is the first Hepburn spelling.
     pub romanji: &'static str,
-    pub alt_romanji: Option<&'static str>,
+    pub hepburn: &'static [&'static str],
+    pub kunrei: &'static [&'static str],
+    pub nihon: &'static [&'static str],
+    pub ime: &'static [&'static str],
 }
 
-pub fn get_translations(kana: &[String]) -> Vec<&'static KanaTranslation> {
-    kana.iter()
-        .map(|k| KANA_TO_ROMANJI.get(k)
-            .unwrap_or_else(|| panic!("kana not found: {}", k))
-        ).collect()
+impl KanaTranslation {
+
+    /// The URL of the bundled audio clip of the kana, if it has one.
+    pub fn audio_clip(&self) -> Option<&'static str> {
+        audio_clip(self.symbol)
+    }
+
+    /// The spellings of the kana within the given romanization system.
+    pub fn spellings(&self, system: RomanizationSystem) -> &'static [&'static str] {
+        match system {
+            RomanizationSystem::Hepburn => self.hepburn,
+            RomanizationSystem::KunreiShiki => self.kunrei,
+            RomanizationSystem::NihonShiki => self.nihon,
+            RomanizationSystem::Ime => self.ime,
+        }
+    }
+
+    /// The distinct spellings of the kana within any of the given romanization systems.
+    /// Kana which have no spelling within these systems (i.e. the extended katakana
+    ///   within Kunrei-shiki) fall back to their Hepburn spellings.
+    pub fn accepted_spellings(&self, systems: RomanizationSystems) -> Vec<&'static str> {
+        let mut spellings: Vec<&'static str> = Vec::new();
+        for spelling in systems.iter().flat_map(|system| self.spellings(system)) {
+            if !spellings.contains(spelling) {
+                spellings.push(spelling);
+            }
+        }
+        if spellings.is_empty() {
+            spellings.extend(self.hepburn);
+        }
+        spellings
+    }
+}
+
+/// The translation of a Kana, which every Kana is guaranteed to have.
+pub(crate) fn kana_translation(kana: Kana) -> &'static KanaTranslation {
+    &TRANSLATIONS[kana.0 as usize]
+}
+
+pub fn get_translation(kana: &str) -> Option<&'static KanaTranslation> {
+    Kana::from_symbol(kana)
+        .map(kana_translation)
+        .or_else(|| COMPOUND_TRANSLATIONS.get(kana))
+}
+
+/// Look up the translations of all of the given kana.
+/// If any kana could not be found, the error lists every one which could not.
+pub fn get_translations(kana: &[&str]) -> Result<Vec<&'static KanaTranslation>, TranslationError> {
+    let mut translations = Vec::with_capacity(kana.len());
+    let mut unknown = Vec::new();
+    for &k in kana {
+        match get_translation(k) {
+            Some(translation) => translations.push(translation),
+            None => unknown.push(k.to_string()),
+        }
+    }
+    match unknown.is_empty() {
+        true => Ok(translations),
+        false => Err(TranslationError::UnknownSymbols(unknown)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TranslationError {
+    /// The symbols which do not have a translation.
+    UnknownSymbols(Vec<String>),
+}
+
+impl std::fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownSymbols(symbols) => {
+                write!(f, "kana not found: {}", symbols.join(", "))
+            },
+        }
+    }
+}
+
+impl std::error::Error for TranslationError {}
+
+/// Convert any katakana within the text to the equivalent hiragana.
+pub fn to_hiragana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
 }
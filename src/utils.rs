@@ -1,3 +1,6 @@
+use wasm_bindgen::{closure::Closure, JsCast};
+use yew::Callback;
+use yew::web_sys::EventTarget;
 
 macro_rules! some_if{
     ($condition:expr,$some:expr) => {{
@@ -8,4 +11,40 @@ macro_rules! some_if{
     }}
 }
 
-pub(crate) use some_if;
\ No newline at end of file
+pub(crate) use some_if;
+/// The number of milliseconds elapsed since the page was loaded.
+pub fn now() -> f64 {
+    yew::utils::window().performance().map_or(0.0, |performance| performance.now())
+}
+
+/// Whether the player has asked for animations to be minimized within their system settings.
+pub fn prefers_reduced_motion() -> bool {
+    yew::utils::window()
+        .match_media("(prefers-reduced-motion: reduce)")
+        .ok()
+        .flatten()
+        .is_some_and(|query| query.matches())
+}
+
+/// A listener of an event upon an EventTarget, which stops listening once dropped.
+pub struct EventListener {
+    target: EventTarget,
+    event: &'static str,
+    closure: Closure<dyn Fn()>,
+}
+
+impl EventListener {
+    pub fn new(target: &EventTarget, event: &'static str, callback: Callback<()>) -> Self {
+        let closure = Closure::wrap(Box::new(move || callback.emit(())) as Box<dyn Fn()>);
+        target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref()).ok();
+        Self { target: target.clone(), event, closure }
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        self.target
+            .remove_event_listener_with_callback(self.event, self.closure.as_ref().unchecked_ref())
+            .ok();
+    }
+}
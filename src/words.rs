@@ -0,0 +1,46 @@
+//! This file is where the vocabulary words are accessed.
+//!
+//! The WORDS list is generated by the build.rs script and stored
+//! within the included words_codegen.rs file.
+include!("words_codegen.rs");  // WORDS
+
+use crate::{
+    SyllabogramSegmentation,
+    translations::{get_translation, KanaTranslation},
+};
+
+#[derive(PartialEq)]
+pub struct Word {
+    pub kana: &'static str,
+    pub meaning: &'static str,
+}
+
+impl Word {
+    /// The translation of each syllabogram of the word.
+    /// Returns None if the kana of any syllabogram is not within the given translations,
+    ///   where a sokuon or chōonpu is permitted alongside any kana which is.
+    pub fn translations_within(
+        &self,
+        translations: &[&'static KanaTranslation],
+    ) -> Option<Vec<&'static KanaTranslation>> {
+        self.kana.syllabograms()
+            .map(|syllabogram| {
+                let translation = get_translation(syllabogram)?;
+                if !translations.iter().any(|t| t.kana == translation.kana) {
+                    return None
+                }
+                Some(translation)
+            })
+            .collect()
+    }
+}
+
+/// All words which are made up entirely of the given translations,
+///   along with the translations of their syllabograms.
+pub fn get_words(
+    translations: &[&'static KanaTranslation],
+) -> Vec<(&'static Word, Vec<&'static KanaTranslation>)> {
+    WORDS.iter()
+        .filter_map(|word| word.translations_within(translations).map(|t| (word, t)))
+        .collect()
+}